@@ -10,4 +10,54 @@ pub enum CustomErrors {
     RoundNotOver,
     #[msg("Invalid Asset")]
     InvalidAsset,
+    #[msg("Fee basis points must sum to 10000")]
+    InvalidFeeSplit,
+    #[msg("Invalid round config")]
+    InvalidRoundConfig,
+    #[msg("Current key accounts are required after the first key")]
+    CurrentKeyRequired,
+    #[msg("Invalid number of keys")]
+    InvalidKeyCount,
+    #[msg("Invalid round status for this action")]
+    InvalidRoundStatus,
+    #[msg("Already claimed")]
+    AlreadyClaimed,
+    #[msg("Invalid referrer")]
+    InvalidReferrer,
+    #[msg("Key is not eligible for a prize")]
+    NoPrize,
+    #[msg("Invalid next round")]
+    InvalidNextRound,
+    #[msg("Too many keys sold to cancel the round")]
+    CancelThresholdReached,
+    #[msg("Unauthorized")]
+    Unauthorized,
+    #[msg("Program is paused")]
+    ProgramPaused,
+    #[msg("Token mint is not allowed")]
+    MintNotAllowed,
+    #[msg("Too many allowed mints")]
+    TooManyMints,
+    #[msg("Round is paused")]
+    RoundPaused,
+    #[msg("Round is not paused")]
+    RoundNotPaused,
+    #[msg("Token mint extension is not supported")]
+    UnsupportedMintExtension,
+    #[msg("Invalid transfer fee")]
+    InvalidTransferFee,
+    #[msg("Token accounts are required for token rounds")]
+    TokenAccountsRequired,
+    #[msg("Incinerator is required to burn SOL")]
+    IncineratorRequired,
+    #[msg("Math overflow")]
+    MathOverflow,
+    #[msg("Invalid key metadata")]
+    InvalidKeyMetadata,
+    #[msg("Keys were already sold in this round")]
+    RoundStarted,
+    #[msg("The leader key is frozen until the winner has claimed")]
+    LeaderKeyLocked,
+    #[msg("Token mint does not match the round")]
+    InvalidMint,
 }
@@ -0,0 +1,154 @@
+use anchor_lang::prelude::*;
+
+#[event]
+pub struct ProgramConfigUpdated {
+    pub admin: Pubkey,
+    pub fee_recipient: Pubkey,
+    pub paused: bool,
+}
+
+#[event]
+pub struct AdminTransferred {
+    pub previous_admin: Pubkey,
+    pub new_admin: Pubkey,
+    // false while the new admin has not accepted yet
+    pub accepted: bool,
+}
+
+#[event]
+pub struct RoundCreated {
+    pub round: Pubkey,
+    pub authority: Pubkey,
+    pub seed: u64,
+    pub token_mint: Pubkey,
+    pub collection: Pubkey,
+    pub round_close_slot: u64,
+}
+
+#[event]
+pub struct RoundActivated {
+    pub round: Pubkey,
+    pub pool_vault: Pubkey,
+    pub treasure_vault: Pubkey,
+}
+
+#[event]
+pub struct KeyPurchased {
+    pub round: Pubkey,
+    pub buyer: Pubkey,
+    pub asset: Pubkey,
+    pub key_index: u64,
+    pub quantity: u64,
+    pub price: u64,
+    pub team_fee: u64,
+    pub referral_fee: u64,
+    pub pool_fee: u64,
+    pub burn_fee: u64,
+    pub treasure_fee: u64,
+    pub referrer: Option<Pubkey>,
+}
+
+#[event]
+pub struct TimerExtended {
+    pub round: Pubkey,
+    pub round_close_slot: u64,
+}
+
+#[event]
+pub struct KeyBurned {
+    pub round: Pubkey,
+    pub owner: Pubkey,
+    pub asset: Pubkey,
+    pub key_index: u64,
+    pub quantity: u64,
+    pub payout: u64,
+}
+
+#[event]
+pub struct DividendsClaimed {
+    pub round: Pubkey,
+    pub owner: Pubkey,
+    pub key_index: u64,
+    pub amount: u64,
+}
+
+#[event]
+pub struct ReferrerCreated {
+    pub round: Pubkey,
+    pub referrer: Pubkey,
+}
+
+#[event]
+pub struct ReferralClaimed {
+    pub round: Pubkey,
+    pub referrer: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct WinnerClaimed {
+    pub round: Pubkey,
+    pub claimer: Pubkey,
+    pub key_index: u64,
+    pub prize: u64,
+    // true for the last key, false for a runner up
+    pub is_winner: bool,
+}
+
+#[event]
+pub struct TeamClaimed {
+    pub round: Pubkey,
+    pub authority: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct RoundRolledOver {
+    pub round: Pubkey,
+    pub next_round: Pubkey,
+    pub treasure_amount: u64,
+    pub pool_amount: u64,
+}
+
+#[event]
+pub struct RoundMetadataUpdated {
+    pub round: Pubkey,
+    pub collection_name: Option<String>,
+    pub collection_uri: Option<String>,
+    pub key_name: String,
+    pub key_base_uri: String,
+}
+
+#[event]
+pub struct LeaderThawed {
+    pub round: Pubkey,
+    pub asset: Pubkey,
+    pub owner: Pubkey,
+}
+
+#[event]
+pub struct RoundPaused {
+    pub round: Pubkey,
+    pub paused_at: u64,
+}
+
+#[event]
+pub struct RoundUnpaused {
+    pub round: Pubkey,
+    pub round_close_slot: u64,
+}
+
+#[event]
+pub struct RoundCancelled {
+    pub round: Pubkey,
+    // topped up by the authority to cover every refund
+    pub shortfall: u64,
+}
+
+#[event]
+pub struct KeyRefunded {
+    pub round: Pubkey,
+    pub owner: Pubkey,
+    pub key_index: u64,
+    pub amount: u64,
+}
@@ -0,0 +1,28 @@
+use anchor_lang::prelude::*;
+
+use crate::{errors::CustomErrors, events::*, state::*};
+
+#[derive(Accounts)]
+pub struct AcceptAdminContext<'info> {
+    #[account(constraint = program_config.pending_admin == authority.key() @ CustomErrors::Unauthorized)]
+    pub authority: Signer<'info>,
+
+    #[account(mut, seeds = [b"config"], bump = program_config.bump)]
+    pub program_config: Box<Account<'info, ProgramConfig>>,
+}
+
+impl AcceptAdminContext<'_> {
+    pub fn accept_admin(ctx: Context<AcceptAdminContext>) -> Result<()> {
+        let program_config = &mut ctx.accounts.program_config;
+        let previous_admin = program_config.admin;
+        program_config.admin = ctx.accounts.authority.key();
+        program_config.pending_admin = Pubkey::default();
+
+        emit!(AdminTransferred {
+            previous_admin,
+            new_admin: program_config.admin,
+            accepted: true,
+        });
+        Ok(())
+    }
+}
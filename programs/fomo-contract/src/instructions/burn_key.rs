@@ -1,20 +1,17 @@
 use anchor_lang::{prelude::*, system_program};
-use anchor_spl::token::{Mint, Token, TokenAccount};
-use mpl_core::{
-    accounts::BaseAssetV1,
-    instructions::{BurnV1Cpi, BurnV1InstructionArgs},
-    types::UpdateAuthority,
-};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use mpl_core::instructions::{BurnV1Cpi, BurnV1InstructionArgs};
 
-use crate::{errors::CustomErrors, state::*};
+use crate::{errors::CustomErrors, events::*, state::*, verify_key_asset, VaultAccounts};
 
 #[derive(Accounts)]
 pub struct BurnKeyContext<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
 
-    #[account(mut,constraint = authority_ata.mint ==  token_mint.key(), constraint = authority_ata.owner == authority.key())]
-    pub authority_ata: Box<Account<'info, TokenAccount>>,
+    /// The token account of the authority, omitted for SOL rounds.
+    #[account(mut, token::mint = token_mint, token::authority = authority)]
+    pub authority_ata: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
 
     #[account(mut,
         seeds = [b"round", round_account.seed.to_le_bytes().as_ref()],
@@ -22,6 +19,9 @@ pub struct BurnKeyContext<'info> {
     )]
     pub round_account: Box<Account<'info, Round>>,
 
+    #[account(seeds = [b"config"], bump = program_config.bump)]
+    pub program_config: Box<Account<'info, ProgramConfig>>,
+
     /// CHECK: address check added
     #[account(address = round_account.collection.key())]
     pub collection: AccountInfo<'info>,
@@ -36,12 +36,14 @@ pub struct BurnKeyContext<'info> {
     )]
     pub key_account: Box<Account<'info, NftKey>>,
 
-    pub token_mint: Box<Account<'info, Mint>>,
+    #[account(constraint = token_mint.key() == round_account.token_mint @ CustomErrors::InvalidMint)]
+    pub token_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
 
-    #[account( address = round_account.pool_vault.key() )]
-    pub pool_vault: Box<Account<'info, TokenAccount>>,
+    /// CHECK: the round's pool vault
+    #[account(mut, address = round_account.pool_vault.key() )]
+    pub pool_vault: UncheckedAccount<'info>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
     /// The SPL Noop program.
     /// CHECK: Checked in mpl-core.
     pub log_wrapper: Option<AccountInfo<'info>>,
@@ -56,6 +58,8 @@ pub struct BurnKeyContext<'info> {
 
 impl BurnKeyContext<'_> {
     pub fn validate(&self) -> Result<()> {
+        self.round_account.check_not_paused(&self.program_config)?;
+
         // Not needed:  check if round is over
         // let current_slot = Clock::get().unwrap().slot;
 
@@ -65,12 +69,20 @@ impl BurnKeyContext<'_> {
         //     CustomErrors::RoundOver
         // );
 
-        let asset_data = BaseAssetV1::from_bytes(&self.asset.to_account_info().data.borrow())?;
+        // cancelled rounds hold their funds for refunds
+        require!(
+            self.round_account.status != RoundStatus::Cancelled,
+            CustomErrors::InvalidRoundStatus
+        );
 
-        if asset_data.update_authority != UpdateAuthority::Collection(self.round_account.collection)
-        {
-            return Err(CustomErrors::InvalidKeyAccount.into());
-        }
+        // the leader stays frozen until thaw_leader
+        require!(
+            self.asset.key() != self.round_account.leader_key || !self.round_account.leader_frozen,
+            CustomErrors::LeaderKeyLocked
+        );
+
+        // only the holder of the key can burn it
+        verify_key_asset(&self.asset, &self.round_account, Some(self.authority.key()))?;
         Ok(())
     }
 
@@ -78,15 +90,21 @@ impl BurnKeyContext<'_> {
     pub fn burn(ctx: Context<BurnKeyContext>) -> Result<()> {
         let key_account = &mut ctx.accounts.key_account;
         let round_account = &mut ctx.accounts.round_account;
-        let current_holder_counter = round_account
-            .mint_counter
-            .checked_sub(round_account.nft_burn_counter)
-            .unwrap();
 
         require_neq!(key_account.exited, 1, CustomErrors::InvalidKeyAccount);
 
-        let token_in_pool = ctx.accounts.pool_vault.amount;
-        let avg_amount_pool = token_in_pool.checked_div(current_holder_counter).unwrap();
+        let dividends = key_account.pending_dividends(round_account.pool_per_key_acc)?;
+
+        let vault_accounts = VaultAccounts {
+            currency: round_account.config.currency,
+            token_mint: ctx.accounts.token_mint.as_deref(),
+            token_program: ctx.accounts.token_program.as_ref(),
+        };
+        let authority_ata = ctx
+            .accounts
+            .authority_ata
+            .as_ref()
+            .map(|authority_ata| authority_ata.to_account_info());
 
         let signer_seeds: &[&[&[u8]]] = &[&[
             b"round",
@@ -94,21 +112,33 @@ impl BurnKeyContext<'_> {
             &[round_account.bump],
         ]];
 
-        let transfer_instruction_burn = anchor_spl::token::Transfer {
-            from: ctx.accounts.pool_vault.to_account_info(),
-            to: ctx.accounts.authority_ata.to_account_info(),
-            authority: round_account.to_account_info(),
-        };
-
-        let cpi_ctx_burn = CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            transfer_instruction_burn,
-        )
-        .with_signer(signer_seeds);
-
-        anchor_spl::token::transfer(cpi_ctx_burn, avg_amount_pool)?;
-
-        round_account.nft_burn_counter += 1;
+        vault_accounts.withdraw(
+            &round_account.to_account_info(),
+            signer_seeds,
+            &ctx.accounts.pool_vault.to_account_info(),
+            &ctx.accounts.authority.to_account_info(),
+            authority_ata.as_ref(),
+            dividends,
+        )?;
+
+        key_account.reward_debt = key_account.accrued(round_account.pool_per_key_acc)?;
+        key_account.dividends_claimed = key_account
+            .dividends_claimed
+            .checked_add(dividends)
+            .ok_or(CustomErrors::MathOverflow)?;
+        round_account.refundable_amount = round_account
+            .refundable_amount
+            .checked_sub(key_account.price_paid)
+            .ok_or(CustomErrors::MathOverflow)?;
+        // the fee may have changed since the purchase
+        round_account.refund_fees = round_account.refund_fees.saturating_sub(
+            vault_accounts.amount_with_transfer_fee(key_account.price_paid)?
+                - key_account.price_paid,
+        );
+        round_account.nft_burn_counter = round_account
+            .nft_burn_counter
+            .checked_add(key_account.quantity)
+            .ok_or(CustomErrors::MathOverflow)?;
 
         key_account.exited = 1;
 
@@ -125,6 +155,15 @@ impl BurnKeyContext<'_> {
             },
         }
         .invoke()?;
+
+        emit!(KeyBurned {
+            round: ctx.accounts.round_account.key(),
+            owner: ctx.accounts.authority.key(),
+            asset: ctx.accounts.asset.key(),
+            key_index: ctx.accounts.key_account.key_index,
+            quantity: ctx.accounts.key_account.quantity,
+            payout: dividends,
+        });
         Ok(())
     }
 }
@@ -0,0 +1,149 @@
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{errors::CustomErrors, events::*, state::*, VaultAccounts};
+
+#[derive(Accounts)]
+pub struct CancelRoundContext<'info> {
+    #[account(mut, constraint = round_account.authority == authority.key())]
+    pub authority: Signer<'info>,
+
+    /// The token account of the authority, omitted for SOL rounds.
+    #[account(mut, token::mint = token_mint, token::authority = authority)]
+    pub authority_ata: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(mut, seeds = [b"round", round_account.seed.to_le_bytes().as_ref()],bump = round_account.bump )]
+    pub round_account: Box<Account<'info, Round>>,
+
+    #[account(seeds = [b"config"], bump = program_config.bump)]
+    pub program_config: Box<Account<'info, ProgramConfig>>,
+
+    #[account(constraint = token_mint.key() == round_account.token_mint @ CustomErrors::InvalidMint)]
+    pub token_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    /// CHECK: the round's team vault
+    #[account(mut,
+        address = round_account.team_vault.key()
+    )]
+    pub team_vault: UncheckedAccount<'info>,
+
+    /// CHECK: the round's pool vault
+    #[account(mut,address = round_account.pool_vault.key() )]
+    pub pool_vault: UncheckedAccount<'info>,
+
+    /// CHECK: the round's treasure vault
+    #[account(mut,
+           address = round_account.treasure_vault.key()
+    )]
+    pub treasure_vault: UncheckedAccount<'info>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+}
+
+impl CancelRoundContext<'_> {
+    pub fn validate(&mut self) -> Result<()> {
+        self.round_account
+            .credit_program_pause(&self.program_config);
+
+        require!(
+            self.round_account.status == RoundStatus::Active,
+            CustomErrors::InvalidRoundStatus
+        );
+        require_gt!(
+            self.round_account.config.cancel_key_threshold,
+            self.round_account.mint_counter,
+            CustomErrors::CancelThresholdReached
+        );
+
+        // only live rounds can be cancelled, a paused round's timer stopped at paused_at
+        let now = if self.round_account.paused {
+            self.round_account.paused_at
+        } else {
+            self.round_account.now()?
+        };
+        require_gt!(
+            self.round_account.round_close_slot,
+            now,
+            CustomErrors::RoundOver
+        );
+        let hard_end_slot = self.round_account.config.round_hard_end_slot;
+        if hard_end_slot != 0 {
+            require_gt!(hard_end_slot, now, CustomErrors::RoundOver);
+        }
+        Ok(())
+    }
+
+    #[access_control(ctx.accounts.validate())]
+    pub fn cancel(ctx: Context<CancelRoundContext>) -> Result<()> {
+        let round_account = &mut ctx.accounts.round_account;
+
+        let vault_accounts = VaultAccounts {
+            currency: round_account.config.currency,
+            token_mint: ctx.accounts.token_mint.as_deref(),
+            token_program: ctx.accounts.token_program.as_ref(),
+        };
+        let authority_ata = ctx
+            .accounts
+            .authority_ata
+            .as_ref()
+            .map(|authority_ata| authority_ata.to_account_info());
+        let team_vault = ctx.accounts.team_vault.to_account_info();
+        let pool_vault = ctx.accounts.pool_vault.to_account_info();
+        let treasure_vault = ctx.accounts.treasure_vault.to_account_info();
+
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"round",
+            &round_account.seed.to_le_bytes(),
+            &[round_account.bump],
+        ]];
+
+        // refunds are paid from the treasure vault, move the other vaults into it
+        vault_accounts.withdraw(
+            &round_account.to_account_info(),
+            signer_seeds,
+            &team_vault,
+            &treasure_vault,
+            Some(&treasure_vault),
+            vault_accounts.balance(&team_vault)?,
+        )?;
+
+        vault_accounts.withdraw(
+            &round_account.to_account_info(),
+            signer_seeds,
+            &pool_vault,
+            &treasure_vault,
+            Some(&treasure_vault),
+            vault_accounts.balance(&pool_vault)?,
+        )?;
+
+        // the authority makes up for burned fees, transfer fees and rewards already paid out,
+        // and for the transfer fees of the refunds themselves
+        let shortfall = round_account
+            .refundable_amount
+            .checked_add(round_account.refund_fees)
+            .ok_or(CustomErrors::MathOverflow)?
+            .saturating_sub(vault_accounts.balance(&treasure_vault)?);
+
+        if shortfall > 0 {
+            vault_accounts.deposit(
+                &ctx.accounts.authority.to_account_info(),
+                authority_ata.as_ref(),
+                &treasure_vault,
+                &ctx.accounts.system_program.to_account_info(),
+                vault_accounts.amount_with_transfer_fee(shortfall)?,
+            )?;
+        }
+
+        round_account.referral_owed = 0;
+        round_account.status = RoundStatus::Cancelled;
+
+        emit!(RoundCancelled {
+            round: round_account.key(),
+            shortfall,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,110 @@
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{errors::CustomErrors, events::*, state::*, verify_key_asset, VaultAccounts};
+
+#[derive(Accounts)]
+pub struct ClaimDividendsContext<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// The token account of the authority, omitted for SOL rounds.
+    #[account(mut, token::mint = token_mint, token::authority = authority)]
+    pub authority_ata: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(
+        seeds = [b"round", round_account.seed.to_le_bytes().as_ref()],
+        bump = round_account.bump
+    )]
+    pub round_account: Box<Account<'info, Round>>,
+
+    #[account(seeds = [b"config"], bump = program_config.bump)]
+    pub program_config: Box<Account<'info, ProgramConfig>>,
+
+    /// CHECK: checking later
+    #[account(constraint = asset.key() == key_account.nft_mint.key())]
+    pub asset: UncheckedAccount<'info>,
+
+    #[account(mut,
+        seeds = [b"key",round_account.key().as_ref(),key_account.key_index.to_le_bytes().as_ref()],
+        bump = key_account.bump
+    )]
+    pub key_account: Box<Account<'info, NftKey>>,
+
+    #[account(constraint = token_mint.key() == round_account.token_mint @ CustomErrors::InvalidMint)]
+    pub token_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    /// CHECK: the round's pool vault
+    #[account(mut, address = round_account.pool_vault.key() )]
+    pub pool_vault: UncheckedAccount<'info>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+}
+
+impl ClaimDividendsContext<'_> {
+    pub fn validate(&self) -> Result<()> {
+        self.round_account.check_not_paused(&self.program_config)?;
+
+        // cancelled rounds hold their funds for refunds
+        require!(
+            self.round_account.status != RoundStatus::Cancelled,
+            CustomErrors::InvalidRoundStatus
+        );
+        require_neq!(self.key_account.exited, 1, CustomErrors::InvalidKeyAccount);
+
+        // only the current holder of the key can harvest it
+        verify_key_asset(&self.asset, &self.round_account, Some(self.authority.key()))?;
+        Ok(())
+    }
+
+    #[access_control(ctx.accounts.validate())]
+    pub fn claim_dividends(ctx: Context<ClaimDividendsContext>) -> Result<()> {
+        let key_account = &mut ctx.accounts.key_account;
+        let round_account = &ctx.accounts.round_account;
+
+        let dividends = key_account.pending_dividends(round_account.pool_per_key_acc)?;
+
+        let vault_accounts = VaultAccounts {
+            currency: round_account.config.currency,
+            token_mint: ctx.accounts.token_mint.as_deref(),
+            token_program: ctx.accounts.token_program.as_ref(),
+        };
+        let authority_ata = ctx
+            .accounts
+            .authority_ata
+            .as_ref()
+            .map(|authority_ata| authority_ata.to_account_info());
+
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"round",
+            &round_account.seed.to_le_bytes(),
+            &[round_account.bump],
+        ]];
+
+        vault_accounts.withdraw(
+            &round_account.to_account_info(),
+            signer_seeds,
+            &ctx.accounts.pool_vault.to_account_info(),
+            &ctx.accounts.authority.to_account_info(),
+            authority_ata.as_ref(),
+            dividends,
+        )?;
+
+        key_account.reward_debt = key_account.accrued(round_account.pool_per_key_acc)?;
+        key_account.dividends_claimed = key_account
+            .dividends_claimed
+            .checked_add(dividends)
+            .ok_or(CustomErrors::MathOverflow)?;
+
+        emit!(DividendsClaimed {
+            round: round_account.key(),
+            owner: ctx.accounts.authority.key(),
+            key_index: key_account.key_index,
+            amount: dividends,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,100 @@
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{errors::CustomErrors, events::*, state::*, VaultAccounts};
+
+#[derive(Accounts)]
+pub struct ClaimReferralContext<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// The token account of the authority, omitted for SOL rounds.
+    #[account(mut, token::mint = token_mint, token::authority = authority)]
+    pub authority_ata: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(mut, seeds = [b"round", round_account.seed.to_le_bytes().as_ref()],bump = round_account.bump )]
+    pub round_account: Box<Account<'info, Round>>,
+
+    #[account(seeds = [b"config"], bump = program_config.bump)]
+    pub program_config: Box<Account<'info, ProgramConfig>>,
+
+    #[account(mut,
+        seeds = [b"referrer", round_account.key().as_ref(), authority.key().as_ref()],
+        bump = referrer_account.bump
+    )]
+    pub referrer_account: Box<Account<'info, Referrer>>,
+
+    #[account(constraint = token_mint.key() == round_account.token_mint @ CustomErrors::InvalidMint)]
+    pub token_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    /// CHECK: the round's team vault
+    #[account(mut,
+        address = round_account.team_vault.key()
+    )]
+    pub team_vault: UncheckedAccount<'info>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+}
+
+impl ClaimReferralContext<'_> {
+    pub fn validate(&self) -> Result<()> {
+        self.round_account.check_not_paused(&self.program_config)?;
+
+        // cancelled rounds hold their funds for refunds
+        require!(
+            self.round_account.status != RoundStatus::Cancelled,
+            CustomErrors::InvalidRoundStatus
+        );
+        Ok(())
+    }
+
+    #[access_control(ctx.accounts.validate())]
+    pub fn claim_referral(ctx: Context<ClaimReferralContext>) -> Result<()> {
+        let round_account = &mut ctx.accounts.round_account;
+        let referrer_account = &mut ctx.accounts.referrer_account;
+        let amount = referrer_account.unclaimed()?;
+
+        let vault_accounts = VaultAccounts {
+            currency: round_account.config.currency,
+            token_mint: ctx.accounts.token_mint.as_deref(),
+            token_program: ctx.accounts.token_program.as_ref(),
+        };
+        let authority_ata = ctx
+            .accounts
+            .authority_ata
+            .as_ref()
+            .map(|authority_ata| authority_ata.to_account_info());
+
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"round",
+            &round_account.seed.to_le_bytes(),
+            &[round_account.bump],
+        ]];
+
+        // referral rewards are held in the team vault until claimed
+        vault_accounts.withdraw(
+            &round_account.to_account_info(),
+            signer_seeds,
+            &ctx.accounts.team_vault.to_account_info(),
+            &ctx.accounts.authority.to_account_info(),
+            authority_ata.as_ref(),
+            amount,
+        )?;
+
+        referrer_account.claimed = referrer_account.rewards;
+        round_account.referral_owed = round_account
+            .referral_owed
+            .checked_sub(amount)
+            .ok_or(CustomErrors::MathOverflow)?;
+
+        emit!(ReferralClaimed {
+            round: round_account.key(),
+            referrer: ctx.accounts.authority.key(),
+            amount,
+        });
+
+        Ok(())
+    }
+}
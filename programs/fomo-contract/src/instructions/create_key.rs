@@ -1,13 +1,13 @@
-use anchor_lang::{prelude::*, system_program};
-use anchor_spl::token::{Mint, Token, TokenAccount};
+use anchor_lang::{prelude::*, solana_program::incinerator, system_program};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 use mpl_core::{
     instructions::{CreateV2Cpi, CreateV2InstructionArgs, UpdatePluginV1Cpi},
     types::{DataState, FreezeDelegate},
 };
 
 use crate::{
-    errors::CustomErrors, state::*, Config, BURN_FEE_BASIS_POINTS, POOL_FEE_BASIS_POINTS,
-    SLOT_TO_CHANGE, TEAM_FEE_BASIS_POINTS, TREASURE_FEE_BASIS_POINTS,
+    basis_points_of, errors::CustomErrors, events::*, state::*, Config, VaultAccounts,
+    MAX_KEYS_PER_PURCHASE,
 };
 
 #[derive(Accounts)]
@@ -15,47 +15,68 @@ pub struct CreateKeyContext<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
 
-    #[account(mut,constraint = authority_ata.mint ==  token_mint.key(), constraint = authority_ata.owner == authority.key())]
-    pub authority_ata: Box<Account<'info, TokenAccount>>,
+    /// The buyer's token account, omitted for SOL rounds.
+    #[account(mut, token::mint = token_mint, token::authority = authority)]
+    pub authority_ata: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
     /// The address of the new asset.
     #[account(mut)]
     pub asset: Signer<'info>,
 
-    /// CHECK: checked
-    #[account(mut, address = current_key_account.nft_mint)]
-    pub current_asset: AccountInfo<'info>,
+    /// The asset of the current leader, omitted for the first key of the round.
+    /// CHECK: checked against the round leader in validate
+    #[account(mut)]
+    pub current_asset: Option<AccountInfo<'info>>,
 
     #[account(mut, seeds = [b"round", round_account.seed.to_le_bytes().as_ref()],bump = round_account.bump )]
     pub round_account: Box<Account<'info, Round>>,
 
+    #[account(seeds = [b"config"], bump = program_config.bump)]
+    pub program_config: Box<Account<'info, ProgramConfig>>,
+
     /// The collection to which the asset belongs.
     /// CHECK: Checked in mpl-core.
     #[account(mut, address = round_account.collection.key())]
     pub collection: AccountInfo<'info>,
 
-    #[account(init, payer = authority,space = 8 + NftKey::INIT_SPACE, seeds = [b"key",round_account.key().as_ref(),(round_account.mint_counter + 1).to_le_bytes().as_ref()],bump)]
+    #[account(init, payer = authority,space = 8 + NftKey::INIT_SPACE, seeds = [b"key",round_account.key().as_ref(),round_account.mint_counter.saturating_add(1).to_le_bytes().as_ref()],bump)]
     pub key_account: Box<Account<'info, NftKey>>,
 
-    #[account(mut, seeds = [b"key",round_account.key().as_ref(),round_account.mint_counter.to_le_bytes().as_ref()],bump = current_key_account.bump)]
-    pub current_key_account: Box<Account<'info, NftKey>>,
+    /// The latest key, omitted for the first key of the round.
+    #[account(mut, seeds = [b"key",round_account.key().as_ref(),round_account.last_key_index.to_le_bytes().as_ref()],bump = current_key_account.bump)]
+    pub current_key_account: Option<Box<Account<'info, NftKey>>>,
 
-    #[account(mut)]
-    pub token_mint: Box<Account<'info, Mint>>,
+    /// The referrer of this purchase, if any.
+    #[account(mut,
+        seeds = [b"referrer", round_account.key().as_ref(), referrer_account.authority.as_ref()],
+        bump = referrer_account.bump
+    )]
+    pub referrer_account: Option<Box<Account<'info, Referrer>>>,
+
+    #[account(mut, constraint = token_mint.key() == round_account.token_mint @ CustomErrors::InvalidMint)]
+    pub token_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
 
+    /// CHECK: the round's team vault
     #[account(mut,
         address = round_account.team_vault.key()
     )]
-    pub team_vault: Box<Account<'info, TokenAccount>>,
+    pub team_vault: UncheckedAccount<'info>,
 
+    /// CHECK: the round's pool vault
     #[account(mut,address = round_account.pool_vault.key() )]
-    pub pool_vault: Box<Account<'info, TokenAccount>>,
+    pub pool_vault: UncheckedAccount<'info>,
 
+    /// CHECK: the round's treasure vault
     #[account(mut,
            address = round_account.treasure_vault.key()
     )]
-    pub treasure_vault: Box<Account<'info, TokenAccount>>,
+    pub treasure_vault: UncheckedAccount<'info>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
 
-    pub token_program: Program<'info, Token>,
+    /// Receives the burn fee of SOL rounds.
+    /// CHECK: the incinerator address
+    #[account(mut, address = incinerator::ID)]
+    pub incinerator: Option<UncheckedAccount<'info>>,
 
     /// The SPL Noop program.
     /// CHECK: Checked in mpl-core.
@@ -71,124 +92,239 @@ pub struct CreateKeyContext<'info> {
 }
 
 impl CreateKeyContext<'_> {
-    pub fn validate(&self) -> Result<()> {
-        let current_slot = Clock::get().unwrap().slot;
+    pub fn validate(&mut self, count: u64) -> Result<()> {
+        self.round_account.check_not_paused(&self.program_config)?;
+        self.round_account
+            .credit_program_pause(&self.program_config);
+
+        require!(
+            count > 0 && count <= MAX_KEYS_PER_PURCHASE,
+            CustomErrors::InvalidKeyCount
+        );
+
+        require!(
+            self.round_account.status == RoundStatus::Active,
+            CustomErrors::InvalidRoundStatus
+        );
+
+        let now = self.round_account.now()?;
 
         // check if round is over
         require_gt!(
             self.round_account.round_close_slot,
-            current_slot,
+            now,
             CustomErrors::RoundOver
         );
 
+        // no purchases past the hard end, whatever the timer says
+        let hard_end_slot = self.round_account.config.round_hard_end_slot;
+        if hard_end_slot != 0 {
+            require_gt!(hard_end_slot, now, CustomErrors::RoundOver);
+        }
+
+        // no self referrals
+        if let Some(referrer_account) = &self.referrer_account {
+            require_keys_neq!(
+                referrer_account.authority,
+                self.authority.key(),
+                CustomErrors::InvalidReferrer
+            );
+        }
+
+        // the first key has no previous key to thaw
+        if self.round_account.mint_counter == 0 {
+            require!(
+                self.current_asset.is_none() && self.current_key_account.is_none(),
+                CustomErrors::InvalidKeyAccount
+            );
+            return Ok(());
+        }
+
+        match (&self.current_asset, &self.current_key_account) {
+            (Some(current_asset), Some(current_key_account)) => {
+                require_keys_eq!(
+                    current_asset.key(),
+                    current_key_account.nft_mint,
+                    CustomErrors::InvalidKeyAccount
+                );
+                // only the frozen leader is handed over
+                require_keys_eq!(
+                    current_asset.key(),
+                    self.round_account.leader_key,
+                    CustomErrors::InvalidKeyAccount
+                );
+            }
+            _ => return Err(CustomErrors::CurrentKeyRequired.into()),
+        }
+
         Ok(())
     }
 
-    #[access_control(ctx.accounts.validate())]
-    pub fn create_key(ctx: Context<CreateKeyContext>) -> Result<()> {
+    #[access_control(ctx.accounts.validate(count))]
+    pub fn create_key(ctx: Context<CreateKeyContext>, count: u64) -> Result<()> {
         let key_account = &mut ctx.accounts.key_account;
         let round_account = &mut ctx.accounts.round_account;
-        let current_counter = round_account.mint_counter + 1;
-        let current_slot = Clock::get().unwrap().slot;
-        let current_key_account = &mut ctx.accounts.current_key_account;
+        let first_index = round_account
+            .mint_counter
+            .checked_add(1)
+            .ok_or(CustomErrors::MathOverflow)?;
+        let now = round_account.now()?;
+
+        let round_config = round_account.config;
+        let total_amount_for_index = round_account.quote_keys(count)?;
+        let pool_amount =
+            basis_points_of(total_amount_for_index, round_config.pool_fee_basis_points)?;
+        let team_amount =
+            basis_points_of(total_amount_for_index, round_config.team_fee_basis_points)?;
+        let burn_amount =
+            basis_points_of(total_amount_for_index, round_config.burn_fee_basis_points)?;
+        let treasure_amount = basis_points_of(
+            total_amount_for_index,
+            round_config.treasure_fee_basis_points,
+        )?;
+        // every share is rounded down, the buyer is charged their sum
+        let price_paid = [team_amount, pool_amount, burn_amount, treasure_amount]
+            .iter()
+            .try_fold(0u64, |total, amount| total.checked_add(*amount))
+            .ok_or(CustomErrors::MathOverflow)?;
+
+        let vault_accounts = VaultAccounts {
+            currency: round_config.currency,
+            token_mint: ctx.accounts.token_mint.as_deref(),
+            token_program: ctx.accounts.token_program.as_ref(),
+        };
+        let authority = ctx.accounts.authority.to_account_info();
+        let authority_ata = ctx
+            .accounts
+            .authority_ata
+            .as_ref()
+            .map(|authority_ata| authority_ata.to_account_info());
+        let system_program = ctx.accounts.system_program.to_account_info();
+
+        // Token-2022 transfer fees are withheld from what reaches the vaults
+        let pool_received = pool_amount - vault_accounts.transfer_fee(pool_amount)?;
+        let team_received = team_amount - vault_accounts.transfer_fee(team_amount)?;
+
+        // pool fees go to the keys already live, the new keys earn from the next purchase on
+        round_account.distribute_pool(pool_received)?;
 
         key_account.create(CreateKeyArgs {
             nft_mint: ctx.accounts.asset.key(),
             bump: ctx.bumps.key_account,
-            key_index: current_counter,
-        });
-
-        // assuming token as 6 decimal
-        let total_amount_for_index = 1000_000 * 10 * current_counter;
-
-        round_account.mint_counter = current_counter;
-        round_account.round_close_slot = current_slot + SLOT_TO_CHANGE;
-
-        // Burn Transfer Ix
-        let transfer_instruction_burn = anchor_spl::token::Transfer {
-            from: ctx.accounts.authority_ata.to_account_info(),
-            to: ctx.accounts.team_vault.to_account_info(),
-            authority: ctx.accounts.authority.to_account_info(),
-        };
-
-        let cpi_ctx_burn = CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            transfer_instruction_burn,
-        );
-
-        anchor_spl::token::transfer(
-            cpi_ctx_burn,
-            total_amount_for_index
-                .checked_mul(BURN_FEE_BASIS_POINTS)
-                .unwrap()
-                .checked_div(10000)
-                .unwrap(),
+            key_index: first_index,
+            quantity: count,
+            pool_per_key_acc: round_account.pool_per_key_acc,
+            price_paid,
+            mint_slot: Clock::get()?.slot,
+            buyer: ctx.accounts.authority.key(),
+        })?;
+
+        round_account.refundable_amount = round_account
+            .refundable_amount
+            .checked_add(price_paid)
+            .ok_or(CustomErrors::MathOverflow)?;
+        // refunds are sent with their transfer fee so the holder gets price_paid back
+        round_account.refund_fees = round_account
+            .refund_fees
+            .checked_add(vault_accounts.amount_with_transfer_fee(price_paid)? - price_paid)
+            .ok_or(CustomErrors::MathOverflow)?;
+
+        round_account.mint_counter = round_account
+            .mint_counter
+            .checked_add(count)
+            .ok_or(CustomErrors::MathOverflow)?;
+        round_account.last_key_index = first_index;
+        round_account.set_leader(ctx.accounts.asset.key(), ctx.accounts.authority.key());
+        round_account.extend_timer(count, now);
+
+        // Burn Ix
+        vault_accounts.burn(
+            &authority,
+            authority_ata.as_ref(),
+            ctx.accounts
+                .incinerator
+                .as_ref()
+                .map(|incinerator| incinerator.as_ref()),
+            &system_program,
+            burn_amount,
         )?;
 
-        // Team Transfer Ix
-        let transfer_instruction_team = anchor_spl::token::Transfer {
-            from: ctx.accounts.authority_ata.to_account_info(),
-            to: ctx.accounts.team_vault.to_account_info(),
-            authority: ctx.accounts.authority.to_account_info(),
-        };
-
-        let cpi_ctx_team = CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            transfer_instruction_team,
-        );
+        round_account.burned_amount = round_account
+            .burned_amount
+            .checked_add(burn_amount)
+            .ok_or(CustomErrors::MathOverflow)?;
+
+        // referral rewards are carved out of the team fee and stay in the team vault
+        let mut referral_amount = 0;
+        if let Some(referrer_account) = ctx.accounts.referrer_account.as_mut() {
+            referral_amount = basis_points_of(
+                total_amount_for_index,
+                round_config.referral_fee_basis_points,
+            )?
+            .min(team_received);
+            referrer_account.add_referral(referral_amount)?;
+            round_account.referral_owed = round_account
+                .referral_owed
+                .checked_add(referral_amount)
+                .ok_or(CustomErrors::MathOverflow)?;
+        }
 
-        anchor_spl::token::transfer(
-            cpi_ctx_team,
-            total_amount_for_index
-                .checked_mul(TEAM_FEE_BASIS_POINTS)
-                .unwrap()
-                .checked_div(10000)
-                .unwrap(),
+        // Team Transfer Ix
+        vault_accounts.deposit(
+            &authority,
+            authority_ata.as_ref(),
+            &ctx.accounts.team_vault.to_account_info(),
+            &system_program,
+            team_amount,
         )?;
 
         // Pool Transfer Ix
-        let transfer_instruction_pool = anchor_spl::token::Transfer {
-            from: ctx.accounts.authority_ata.to_account_info(),
-            to: ctx.accounts.pool_vault.to_account_info(),
-            authority: ctx.accounts.authority.to_account_info(),
-        };
-
-        let cpi_ctx_pool = CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            transfer_instruction_pool,
-        );
-
-        anchor_spl::token::transfer(
-            cpi_ctx_pool,
-            total_amount_for_index
-                .checked_mul(POOL_FEE_BASIS_POINTS)
-                .unwrap()
-                .checked_div(10000)
-                .unwrap(),
+        vault_accounts.deposit(
+            &authority,
+            authority_ata.as_ref(),
+            &ctx.accounts.pool_vault.to_account_info(),
+            &system_program,
+            pool_amount,
         )?;
 
         // Treasure Transfer Ix
-        let transfer_instruction_treasure = anchor_spl::token::Transfer {
-            from: ctx.accounts.authority_ata.to_account_info(),
-            to: ctx.accounts.treasure_vault.to_account_info(),
-            authority: ctx.accounts.authority.to_account_info(),
-        };
-
-        let cpi_ctx_treasure = CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            transfer_instruction_treasure,
-        );
-
-        anchor_spl::token::transfer(
-            cpi_ctx_treasure,
-            total_amount_for_index
-                .checked_mul(TREASURE_FEE_BASIS_POINTS)
-                .unwrap()
-                .checked_div(10000)
-                .unwrap(),
+        vault_accounts.deposit(
+            &authority,
+            authority_ata.as_ref(),
+            &ctx.accounts.treasure_vault.to_account_info(),
+            &system_program,
+            treasure_amount,
         )?;
 
-        let config = Config::get_master(round_account.key());
+        emit!(KeyPurchased {
+            round: round_account.key(),
+            buyer: ctx.accounts.authority.key(),
+            asset: ctx.accounts.asset.key(),
+            key_index: first_index,
+            quantity: count,
+            price: total_amount_for_index,
+            team_fee: team_amount - referral_amount,
+            referral_fee: referral_amount,
+            pool_fee: pool_amount,
+            burn_fee: burn_amount,
+            treasure_fee: treasure_amount,
+            referrer: ctx
+                .accounts
+                .referrer_account
+                .as_ref()
+                .map(|referrer_account| referrer_account.authority),
+        });
+        emit!(TimerExtended {
+            round: round_account.key(),
+            round_close_slot: round_account.round_close_slot,
+        });
+
+        let config = Config::get_master(
+            round_account.key(),
+            round_account.seed,
+            &round_account.key_metadata,
+            key_account,
+        );
         let seeds: &[&[&[u8]]] = &[&[
             b"round",
             &round_account.seed.to_le_bytes(),
@@ -214,8 +350,13 @@ impl CreateKeyContext<'_> {
         }
         .invoke_signed(seeds)?;
 
+        // thaw the previous leader, if any
+        let Some(current_asset) = ctx.accounts.current_asset.as_ref() else {
+            return Ok(());
+        };
+
         UpdatePluginV1Cpi {
-            asset: &ctx.accounts.current_asset,
+            asset: current_asset,
             __program: &ctx.accounts.mpl_core,
             payer: &ctx.accounts.authority.to_account_info(),
             system_program: &ctx.accounts.system_program.to_account_info(),
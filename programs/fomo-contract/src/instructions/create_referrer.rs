@@ -0,0 +1,42 @@
+use anchor_lang::{prelude::*, system_program};
+
+use crate::{events::*, state::*};
+
+#[derive(Accounts)]
+pub struct CreateReferrerContext<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [b"round", round_account.seed.to_le_bytes().as_ref()],bump = round_account.bump)]
+    pub round_account: Box<Account<'info, Round>>,
+
+    #[account(seeds = [b"config"], bump = program_config.bump)]
+    pub program_config: Box<Account<'info, ProgramConfig>>,
+
+    #[account(init, payer = authority, space = 8 + Referrer::INIT_SPACE, seeds = [b"referrer", round_account.key().as_ref(), authority.key().as_ref()], bump)]
+    pub referrer_account: Box<Account<'info, Referrer>>,
+
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+}
+
+impl CreateReferrerContext<'_> {
+    pub fn validate(&self) -> Result<()> {
+        self.round_account.check_not_paused(&self.program_config)
+    }
+
+    #[access_control(ctx.accounts.validate())]
+    pub fn create_referrer(ctx: Context<CreateReferrerContext>) -> Result<()> {
+        ctx.accounts.referrer_account.create(CreateReferrerArgs {
+            round: ctx.accounts.round_account.key(),
+            authority: ctx.accounts.authority.key(),
+            bump: ctx.bumps.referrer_account,
+        });
+
+        emit!(ReferrerCreated {
+            round: ctx.accounts.round_account.key(),
+            referrer: ctx.accounts.authority.key(),
+        });
+        Ok(())
+    }
+}
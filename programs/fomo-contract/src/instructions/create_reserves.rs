@@ -1,7 +1,7 @@
 use anchor_lang::{prelude::*, system_program};
-use anchor_spl::token::{Mint, Token, TokenAccount};
+use anchor_spl::token_interface::{Mint, TokenInterface};
 
-use crate::state::*;
+use crate::{errors::CustomErrors, events::*, state::*, VaultAccounts};
 
 #[derive(Accounts)]
 pub struct CreateRoundReservesContext<'info> {
@@ -11,40 +11,72 @@ pub struct CreateRoundReservesContext<'info> {
     #[account(mut, seeds = [b"round", round_account.seed.to_le_bytes().as_ref()],bump = round_account.bump)]
     pub round_account: Box<Account<'info, Round>>,
 
-    pub token_mint: Box<Account<'info, Mint>>,
-
-    #[account(init,
-        seeds = [b"pool", round_account.key().as_ref()],
-        bump,
-        payer = authority,
-        token::mint = token_mint,
-        token::authority = round_account,
-    )]
-    pub pool_vault: Box<Account<'info, TokenAccount>>,
-
-    #[account(init,
-        seeds = [b"vault", round_account.key().as_ref()],
-        bump,
-        payer = authority,
-        token::mint = token_mint,
-        token::authority = round_account,
-    )]
-    pub treasure_vault: Box<Account<'info, TokenAccount>>,
-
-    pub token_program: Program<'info, Token>,
+    #[account(seeds = [b"config"], bump = program_config.bump)]
+    pub program_config: Box<Account<'info, ProgramConfig>>,
+
+    #[account(mint::token_program = token_program, constraint = token_mint.key() == round_account.token_mint @ CustomErrors::InvalidMint)]
+    pub token_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    /// CHECK: created in the handler, as a token account or a SOL vault
+    #[account(mut, seeds = [b"pool", round_account.key().as_ref()], bump)]
+    pub pool_vault: UncheckedAccount<'info>,
+
+    /// CHECK: created in the handler, as a token account or a SOL vault
+    #[account(mut, seeds = [b"vault", round_account.key().as_ref()], bump)]
+    pub treasure_vault: UncheckedAccount<'info>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
     #[account(address = system_program::ID)]
     pub system_program: Program<'info, System>,
 }
 
 impl CreateRoundReservesContext<'_> {
+    pub fn validate(&self) -> Result<()> {
+        self.round_account.check_not_paused(&self.program_config)?;
+
+        require!(
+            self.round_account.status == RoundStatus::Pending,
+            CustomErrors::InvalidRoundStatus
+        );
+        Ok(())
+    }
+
+    #[access_control(ctx.accounts.validate())]
     pub fn create_reserves(ctx: Context<CreateRoundReservesContext>) -> Result<()> {
         let round_account = &mut ctx.accounts.round_account;
 
+        let vault_accounts = VaultAccounts {
+            currency: round_account.config.currency,
+            token_mint: ctx.accounts.token_mint.as_deref(),
+            token_program: ctx.accounts.token_program.as_ref(),
+        };
+        let round_key = round_account.key();
+        vault_accounts.create_vault(
+            &ctx.accounts.pool_vault.to_account_info(),
+            &[b"pool", round_key.as_ref(), &[ctx.bumps.pool_vault]],
+            &round_account.to_account_info(),
+            &ctx.accounts.authority.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+        )?;
+        vault_accounts.create_vault(
+            &ctx.accounts.treasure_vault.to_account_info(),
+            &[b"vault", round_key.as_ref(), &[ctx.bumps.treasure_vault]],
+            &round_account.to_account_info(),
+            &ctx.accounts.authority.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+        )?;
+
         round_account.create_reserves(RoundCreateReservesArgs {
             treasure_vault: ctx.accounts.treasure_vault.key(),
             pool_vault: ctx.accounts.pool_vault.key(),
         });
 
+        emit!(RoundActivated {
+            round: round_account.key(),
+            pool_vault: round_account.pool_vault,
+            treasure_vault: round_account.treasure_vault,
+        });
+
         Ok(())
     }
 }
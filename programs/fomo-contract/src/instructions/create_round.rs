@@ -1,8 +1,14 @@
 use anchor_lang::{prelude::*, system_program};
-use anchor_spl::token::{Mint, Token, TokenAccount};
-use mpl_core::instructions::{CreateCollectionV2Cpi, CreateCollectionV2InstructionArgs};
+use anchor_spl::{
+    token::spl_token::native_mint,
+    token_interface::{Mint, TokenInterface},
+};
+use mpl_core::instructions::CreateCollectionV2Cpi;
 
-use crate::{state::*, Config, SLOT_TO_CHANGE};
+use crate::{
+    check_mint_extensions, errors::CustomErrors, events::*, state::*, Config, VaultAccounts,
+    SOL_DECIMALS,
+};
 
 #[derive(Accounts)]
 #[instruction(seed:u64)]
@@ -16,18 +22,18 @@ pub struct CreateRoundContext<'info> {
     #[account(init, payer = authority,space = 8 + Round::INIT_SPACE, seeds = [b"round", seed.to_le_bytes().as_ref()],bump)]
     pub round_account: Box<Account<'info, Round>>,
 
-    pub token_mint: Box<Account<'info, Mint>>,
+    /// The token keys are paid in, omitted for SOL rounds.
+    #[account(mint::token_program = token_program)]
+    pub token_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
 
-    #[account(init,
-        seeds = [b"team", round_account.key().as_ref()],
-        bump,
-        payer = authority,
-        token::mint = token_mint,
-        token::authority = round_account,
-    )]
-    pub team_vault: Box<Account<'info, TokenAccount>>,
+    #[account(seeds = [b"config"], bump = program_config.bump)]
+    pub program_config: Box<Account<'info, ProgramConfig>>,
 
-    pub token_program: Program<'info, Token>,
+    /// CHECK: created in the handler, as a token account or a SOL vault
+    #[account(mut, seeds = [b"team", round_account.key().as_ref()], bump)]
+    pub team_vault: UncheckedAccount<'info>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
     /// The SPL Noop program.
     /// CHECK: Checked in mpl-core.
     pub log_wrapper: Option<AccountInfo<'info>>,
@@ -40,15 +46,69 @@ pub struct CreateRoundContext<'info> {
     pub system_program: Program<'info, System>,
 }
 impl CreateRoundContext<'_> {
+    pub fn validate(&self, config: &RoundConfig, key_metadata: &KeyMetadata) -> Result<()> {
+        require!(!self.program_config.paused, CustomErrors::ProgramPaused);
+
+        // SOL rounds are allowed through the native mint
+        let currency_mint = match config.currency {
+            Currency::Token => {
+                let token_mint = self
+                    .token_mint
+                    .as_ref()
+                    .ok_or(CustomErrors::TokenAccountsRequired)?;
+                check_mint_extensions(&token_mint.to_account_info())?;
+                require_eq!(
+                    config.token_decimals,
+                    token_mint.decimals,
+                    CustomErrors::InvalidRoundConfig
+                );
+                token_mint.key()
+            }
+            Currency::Sol => {
+                require!(self.token_mint.is_none(), CustomErrors::InvalidRoundConfig);
+                require_eq!(
+                    config.token_decimals,
+                    SOL_DECIMALS,
+                    CustomErrors::InvalidRoundConfig
+                );
+                native_mint::ID
+            }
+        };
+        require!(
+            self.program_config.is_mint_allowed(&currency_mint),
+            CustomErrors::MintNotAllowed
+        );
+
+        config.validate()?;
+        key_metadata.validate()?;
+
+        if config.round_hard_end_slot != 0 {
+            require_gt!(
+                config.round_hard_end_slot,
+                config.timer_mode.now()?,
+                CustomErrors::InvalidRoundConfig
+            );
+        }
+        Ok(())
+    }
+
+    #[access_control(ctx.accounts.validate(&config, &key_metadata))]
     pub fn create_round(
         ctx: Context<CreateRoundContext>,
         seed: u64,
         name: String,
         uri: String,
+        key_metadata: KeyMetadata,
+        config: RoundConfig,
     ) -> Result<()> {
         let round_account = &mut ctx.accounts.round_account;
+        let token_mint = ctx
+            .accounts
+            .token_mint
+            .as_ref()
+            .map_or(native_mint::ID, |token_mint| token_mint.key());
 
-        let current_slot = Clock::get().unwrap().slot;
+        let now = config.timer_mode.now()?;
         let signer_seeds: &[&[&[u8]]] = &[&[
             b"round",
             &round_account.seed.to_le_bytes(),
@@ -61,14 +121,37 @@ impl CreateRoundContext<'_> {
             team_vault: ctx.accounts.team_vault.key(),
             treasure_vault: Pubkey::default(),
             pool_vault: Pubkey::default(),
-            round_close_slot: current_slot + SLOT_TO_CHANGE,
+            round_close_slot: now
+                .checked_add(config.timer_window)
+                .ok_or(CustomErrors::MathOverflow)?,
             collection: ctx.accounts.collection.key(),
+            token_mint,
+            config,
+            key_metadata,
+            // pauses before the round was created are not credited to it
+            program_paused_time: ctx.accounts.program_config.paused_time(config.timer_mode),
         });
+        round_account.round_close_slot =
+            round_account.cap_close_slot(round_account.round_close_slot, now);
+
+        let vault_accounts = VaultAccounts {
+            currency: config.currency,
+            token_mint: ctx.accounts.token_mint.as_deref(),
+            token_program: ctx.accounts.token_program.as_ref(),
+        };
+        let round_key = round_account.key();
+        vault_accounts.create_vault(
+            &ctx.accounts.team_vault.to_account_info(),
+            &[b"team", round_key.as_ref(), &[ctx.bumps.team_vault]],
+            &round_account.to_account_info(),
+            &ctx.accounts.authority.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+        )?;
 
-        let config = Config::get_collection(round_account.key());
+        let collection_config = Config::get_collection(round_account.key());
         // cpis into metaplex and makes the collection
         CreateCollectionV2Cpi {
-            collection: &ctx.accounts.collection.as_ref(),
+            collection: ctx.accounts.collection.as_ref(),
             payer: &ctx.accounts.authority.to_account_info(),
             update_authority: Some(ctx.accounts.round_account.to_account_info().as_ref()),
             system_program: &ctx.accounts.system_program.to_account_info(),
@@ -76,12 +159,21 @@ impl CreateRoundContext<'_> {
             __args: mpl_core::instructions::CreateCollectionV2InstructionArgs {
                 name,
                 uri,
-                plugins: Some(config.plugins),
+                plugins: Some(collection_config.plugins),
                 external_plugin_adapters: None,
             },
         }
         .invoke_signed(signer_seeds)?;
 
+        emit!(RoundCreated {
+            round: ctx.accounts.round_account.key(),
+            authority: ctx.accounts.authority.key(),
+            seed,
+            token_mint,
+            collection: ctx.accounts.collection.key(),
+            round_close_slot: ctx.accounts.round_account.round_close_slot,
+        });
+
         Ok(())
     }
 }
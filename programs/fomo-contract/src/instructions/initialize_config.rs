@@ -0,0 +1,44 @@
+use anchor_lang::{prelude::*, system_program};
+
+use crate::{errors::CustomErrors, events::*, program::FomoContract, state::*};
+
+#[derive(Accounts)]
+pub struct InitializeConfigContext<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(init, payer = authority, space = 8 + ProgramConfig::INIT_SPACE, seeds = [b"config"], bump)]
+    pub program_config: Box<Account<'info, ProgramConfig>>,
+
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, FomoContract>,
+
+    // only the upgrade authority can set up the config
+    #[account(constraint = program_data.upgrade_authority_address == Some(authority.key()) @ CustomErrors::Unauthorized)]
+    pub program_data: Account<'info, ProgramData>,
+
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+}
+
+impl InitializeConfigContext<'_> {
+    #[access_control(args.validate())]
+    pub fn initialize_config(
+        ctx: Context<InitializeConfigContext>,
+        args: ProgramConfigArgs,
+    ) -> Result<()> {
+        ctx.accounts.program_config.create(
+            ctx.accounts.authority.key(),
+            ctx.bumps.program_config,
+            args,
+        )?;
+
+        let program_config = &ctx.accounts.program_config;
+        emit!(ProgramConfigUpdated {
+            admin: program_config.admin,
+            fee_recipient: program_config.fee_recipient,
+            paused: program_config.paused,
+        });
+        Ok(())
+    }
+}
@@ -1,13 +1,41 @@
+pub mod accept_admin;
 pub mod burn_key;
+pub mod cancel_round;
+pub mod claim_dividends;
+pub mod claim_referral;
 pub mod create_key;
+pub mod create_referrer;
 pub mod create_reserves;
 pub mod create_round;
+pub mod initialize_config;
+pub mod pause_round;
+pub mod refund_key;
+pub mod rollover_round;
 pub mod round_claim;
+pub mod thaw_leader;
+pub mod transfer_admin;
+pub mod unpause_round;
+pub mod update_config;
+pub mod update_round_metadata;
 pub mod winner_claim;
 
+pub use accept_admin::*;
 pub use burn_key::*;
+pub use cancel_round::*;
+pub use claim_dividends::*;
+pub use claim_referral::*;
 pub use create_key::*;
+pub use create_referrer::*;
 pub use create_reserves::*;
 pub use create_round::*;
+pub use initialize_config::*;
+pub use pause_round::*;
+pub use refund_key::*;
+pub use rollover_round::*;
 pub use round_claim::*;
+pub use thaw_leader::*;
+pub use transfer_admin::*;
+pub use unpause_round::*;
+pub use update_config::*;
+pub use update_round_metadata::*;
 pub use winner_claim::*;
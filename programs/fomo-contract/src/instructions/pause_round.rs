@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+
+use crate::{errors::CustomErrors, events::*, state::*};
+
+#[derive(Accounts)]
+pub struct PauseRoundContext<'info> {
+    #[account(constraint = round_account.authority == authority.key())]
+    pub authority: Signer<'info>,
+
+    #[account(mut, seeds = [b"round", round_account.seed.to_le_bytes().as_ref()],bump = round_account.bump )]
+    pub round_account: Box<Account<'info, Round>>,
+}
+
+impl PauseRoundContext<'_> {
+    pub fn validate(&self) -> Result<()> {
+        require!(!self.round_account.paused, CustomErrors::RoundPaused);
+        require!(
+            matches!(
+                self.round_account.status,
+                RoundStatus::Pending | RoundStatus::Active | RoundStatus::Ended
+            ),
+            CustomErrors::InvalidRoundStatus
+        );
+        Ok(())
+    }
+
+    #[access_control(ctx.accounts.validate())]
+    pub fn pause(ctx: Context<PauseRoundContext>) -> Result<()> {
+        let round_account = &mut ctx.accounts.round_account;
+        let now = round_account.now()?;
+        round_account.pause(now);
+
+        emit!(RoundPaused {
+            round: round_account.key(),
+            paused_at: now,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,155 @@
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use mpl_core::{
+    instructions::{UpdatePluginV1Cpi, UpdatePluginV1InstructionArgs},
+    types::Plugin,
+};
+
+use crate::{
+    errors::CustomErrors, events::*, key_attributes, state::*, verify_key_asset, VaultAccounts,
+};
+
+#[derive(Accounts)]
+pub struct RefundKeyContext<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// The token account of the authority, omitted for SOL rounds.
+    #[account(mut, token::mint = token_mint, token::authority = authority)]
+    pub authority_ata: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(mut,
+        seeds = [b"round", round_account.seed.to_le_bytes().as_ref()],
+        bump = round_account.bump
+    )]
+    pub round_account: Box<Account<'info, Round>>,
+
+    #[account(seeds = [b"config"], bump = program_config.bump)]
+    pub program_config: Box<Account<'info, ProgramConfig>>,
+
+    /// CHECK: checking later
+    #[account(mut, constraint = asset.key() == key_account.nft_mint.key())]
+    pub asset: UncheckedAccount<'info>,
+
+    #[account(mut,
+        seeds = [b"key",round_account.key().as_ref(),key_account.key_index.to_le_bytes().as_ref()],
+        bump = key_account.bump
+    )]
+    pub key_account: Box<Account<'info, NftKey>>,
+
+    #[account(constraint = token_mint.key() == round_account.token_mint @ CustomErrors::InvalidMint)]
+    pub token_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    /// CHECK: the round's treasure vault
+    #[account(mut,
+           address = round_account.treasure_vault.key()
+    )]
+    pub treasure_vault: UncheckedAccount<'info>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    /// The collection to which the asset belongs.
+    /// CHECK: address check added
+    #[account(mut, address = round_account.collection.key())]
+    pub collection: AccountInfo<'info>,
+
+    /// The SPL Noop program.
+    /// CHECK: Checked in mpl-core.
+    pub log_wrapper: Option<AccountInfo<'info>>,
+
+    /// The MPL Core program.
+    /// CHECK: Checked in mpl-core.
+    #[account(address = mpl_core::ID)]
+    pub mpl_core: AccountInfo<'info>,
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+}
+
+impl RefundKeyContext<'_> {
+    pub fn validate(&self) -> Result<()> {
+        self.round_account.check_not_paused(&self.program_config)?;
+
+        require!(
+            self.round_account.status == RoundStatus::Cancelled,
+            CustomErrors::InvalidRoundStatus
+        );
+        require_neq!(self.key_account.exited, 1, CustomErrors::InvalidKeyAccount);
+
+        // only the current holder of the key gets the refund
+        verify_key_asset(&self.asset, &self.round_account, Some(self.authority.key()))?;
+        Ok(())
+    }
+
+    #[access_control(ctx.accounts.validate())]
+    pub fn refund(ctx: Context<RefundKeyContext>) -> Result<()> {
+        let key_account = &mut ctx.accounts.key_account;
+        let round_account = &mut ctx.accounts.round_account;
+
+        let vault_accounts = VaultAccounts {
+            currency: round_account.config.currency,
+            token_mint: ctx.accounts.token_mint.as_deref(),
+            token_program: ctx.accounts.token_program.as_ref(),
+        };
+        let authority_ata = ctx
+            .accounts
+            .authority_ata
+            .as_ref()
+            .map(|authority_ata| authority_ata.to_account_info());
+
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"round",
+            &round_account.seed.to_le_bytes(),
+            &[round_account.bump],
+        ]];
+
+        // the holder gets back what they paid, the treasure pays the transfer fee
+        let refund_amount = vault_accounts.amount_with_transfer_fee(key_account.price_paid)?;
+
+        vault_accounts.withdraw(
+            &round_account.to_account_info(),
+            signer_seeds,
+            &ctx.accounts.treasure_vault.to_account_info(),
+            &ctx.accounts.authority.to_account_info(),
+            authority_ata.as_ref(),
+            refund_amount,
+        )?;
+
+        round_account.refundable_amount = round_account
+            .refundable_amount
+            .checked_sub(key_account.price_paid)
+            .ok_or(CustomErrors::MathOverflow)?;
+        // the fee may have changed since the purchase
+        round_account.refund_fees = round_account
+            .refund_fees
+            .saturating_sub(refund_amount - key_account.price_paid);
+        round_account.nft_burn_counter = round_account
+            .nft_burn_counter
+            .checked_add(key_account.quantity)
+            .ok_or(CustomErrors::MathOverflow)?;
+        key_account.exited = 1;
+
+        // show the new state of the key on the asset
+        UpdatePluginV1Cpi {
+            asset: &ctx.accounts.asset.to_account_info(),
+            __program: &ctx.accounts.mpl_core,
+            payer: &ctx.accounts.authority.to_account_info(),
+            system_program: &ctx.accounts.system_program.to_account_info(),
+            log_wrapper: ctx.accounts.log_wrapper.as_ref(),
+            collection: Some(ctx.accounts.collection.as_ref()),
+            authority: Some(round_account.to_account_info().as_ref()),
+            __args: UpdatePluginV1InstructionArgs {
+                plugin: Plugin::Attributes(key_attributes(round_account.seed, key_account, false)),
+            },
+        }
+        .invoke_signed(signer_seeds)?;
+
+        emit!(KeyRefunded {
+            round: round_account.key(),
+            owner: ctx.accounts.authority.key(),
+            key_index: key_account.key_index,
+            amount: key_account.price_paid,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,145 @@
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::token_interface::{Mint, TokenInterface};
+
+use crate::{basis_points_of, errors::CustomErrors, events::*, state::*, VaultAccounts};
+
+#[derive(Accounts)]
+pub struct RolloverRoundContext<'info> {
+    #[account(mut, constraint = round_account.authority == authority.key())]
+    pub authority: Signer<'info>,
+
+    #[account(mut, seeds = [b"round", round_account.seed.to_le_bytes().as_ref()],bump = round_account.bump )]
+    pub round_account: Box<Account<'info, Round>>,
+
+    #[account(seeds = [b"config"], bump = program_config.bump)]
+    pub program_config: Box<Account<'info, ProgramConfig>>,
+
+    #[account(mut,
+        constraint = next_round_account.authority == authority.key(),
+        constraint = next_round_account.key() != round_account.key(),
+        seeds = [b"round", next_round_account.seed.to_le_bytes().as_ref()],
+        bump = next_round_account.bump
+    )]
+    pub next_round_account: Box<Account<'info, Round>>,
+
+    /// The token both rounds are paid in.
+    #[account(constraint = token_mint.key() == round_account.token_mint @ CustomErrors::InvalidMint)]
+    pub token_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    /// CHECK: the round's treasure vault
+    #[account(mut,
+        address = round_account.treasure_vault.key()
+    )]
+    pub treasure_vault: UncheckedAccount<'info>,
+
+    /// CHECK: the round's pool vault
+    #[account(mut,
+        address = round_account.pool_vault.key()
+    )]
+    pub pool_vault: UncheckedAccount<'info>,
+
+    /// CHECK: the next round's treasure vault
+    #[account(mut,
+        address = next_round_account.treasure_vault.key()
+    )]
+    pub next_treasure_vault: UncheckedAccount<'info>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+}
+
+impl RolloverRoundContext<'_> {
+    pub fn validate(&self) -> Result<()> {
+        self.round_account.check_not_paused(&self.program_config)?;
+
+        require!(
+            self.round_account.status == RoundStatus::Settled,
+            CustomErrors::InvalidRoundStatus
+        );
+        require!(
+            self.next_round_account.status == RoundStatus::Active,
+            CustomErrors::InvalidRoundStatus
+        );
+
+        require!(
+            self.next_round_account.config.currency == self.round_account.config.currency,
+            CustomErrors::InvalidNextRound
+        );
+        require_keys_eq!(
+            self.next_round_account.token_mint,
+            self.round_account.token_mint,
+            CustomErrors::InvalidNextRound
+        );
+
+        // rounds are linked once, in both directions
+        require_keys_eq!(
+            self.round_account.next_round,
+            Pubkey::default(),
+            CustomErrors::InvalidNextRound
+        );
+        require_keys_eq!(
+            self.next_round_account.previous_round,
+            Pubkey::default(),
+            CustomErrors::InvalidNextRound
+        );
+        Ok(())
+    }
+
+    #[access_control(ctx.accounts.validate())]
+    pub fn rollover(ctx: Context<RolloverRoundContext>) -> Result<()> {
+        let round_account = &mut ctx.accounts.round_account;
+
+        let vault_accounts = VaultAccounts {
+            currency: round_account.config.currency,
+            token_mint: ctx.accounts.token_mint.as_deref(),
+            token_program: ctx.accounts.token_program.as_ref(),
+        };
+
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"round",
+            &round_account.seed.to_le_bytes(),
+            &[round_account.bump],
+        ]];
+
+        // Treasure Transfer Ix
+        let treasure_amount = basis_points_of(
+            round_account.prize_pool,
+            round_account.config.prize_table.rollover_basis_points,
+        )?;
+
+        vault_accounts.withdraw(
+            &round_account.to_account_info(),
+            signer_seeds,
+            &ctx.accounts.treasure_vault.to_account_info(),
+            &ctx.accounts.next_treasure_vault.to_account_info(),
+            Some(&ctx.accounts.next_treasure_vault.to_account_info()),
+            treasure_amount,
+        )?;
+
+        // Pool Transfer Ix, only the fees no key is entitled to
+        let pool_amount = round_account.pool_unallocated;
+
+        vault_accounts.withdraw(
+            &round_account.to_account_info(),
+            signer_seeds,
+            &ctx.accounts.pool_vault.to_account_info(),
+            &ctx.accounts.next_treasure_vault.to_account_info(),
+            Some(&ctx.accounts.next_treasure_vault.to_account_info()),
+            pool_amount,
+        )?;
+
+        round_account.pool_unallocated = 0;
+        round_account.next_round = ctx.accounts.next_round_account.key();
+        ctx.accounts.next_round_account.previous_round = round_account.key();
+
+        emit!(RoundRolledOver {
+            round: round_account.key(),
+            next_round: round_account.next_round,
+            treasure_amount,
+            pool_amount,
+        });
+
+        Ok(())
+    }
+}
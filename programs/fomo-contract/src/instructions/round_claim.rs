@@ -1,38 +1,57 @@
 use anchor_lang::{prelude::*, system_program};
-use anchor_spl::token::{Mint, Token, TokenAccount};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
-use crate::{errors::CustomErrors, Round};
+use crate::{errors::CustomErrors, events::*, state::*, VaultAccounts};
 
 #[derive(Accounts)]
 pub struct RoundClaimContext<'info> {
     #[account(mut, constraint = round_account.authority == authority.key())]
     pub authority: Signer<'info>,
 
-    #[account(mut,constraint = authority_ata.mint ==  token_mint.key(), constraint = authority_ata.owner == authority.key())]
-    pub authority_ata: Box<Account<'info, TokenAccount>>,
+    /// The token account of the authority, omitted for SOL rounds.
+    #[account(mut, token::mint = token_mint, token::authority = authority)]
+    pub authority_ata: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
 
     #[account(mut, seeds = [b"round", round_account.seed.to_le_bytes().as_ref()],bump = round_account.bump )]
     pub round_account: Box<Account<'info, Round>>,
 
-    pub token_mint: Box<Account<'info, Mint>>,
+    #[account(seeds = [b"config"], bump = program_config.bump)]
+    pub program_config: Box<Account<'info, ProgramConfig>>,
 
-    #[account(
+    #[account(constraint = token_mint.key() == round_account.token_mint @ CustomErrors::InvalidMint)]
+    pub token_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    /// CHECK: the round's team vault
+    #[account(mut,
         address = round_account.team_vault.key()
     )]
-    pub team_vault: Box<Account<'info, TokenAccount>>,
+    pub team_vault: UncheckedAccount<'info>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
     #[account(address = system_program::ID)]
     pub system_program: Program<'info, System>,
 }
 
 impl RoundClaimContext<'_> {
-    pub fn validate(&self) -> Result<()> {
-        let current_slot = Clock::get().unwrap().slot;
+    pub fn validate(&mut self) -> Result<()> {
+        self.round_account.check_not_paused(&self.program_config)?;
+        self.round_account
+            .credit_program_pause(&self.program_config);
+
+        require!(
+            self.round_account.is_claimable(),
+            CustomErrors::InvalidRoundStatus
+        );
+        require!(
+            !self.round_account.team_claimed,
+            CustomErrors::AlreadyClaimed
+        );
+
+        let now = self.round_account.now()?;
         // check if round is over or not
 
         require_gt!(
-            current_slot,
+            now,
             self.round_account.round_close_slot,
             CustomErrors::RoundNotOver
         );
@@ -42,26 +61,48 @@ impl RoundClaimContext<'_> {
     pub fn claim(ctx: Context<RoundClaimContext>) -> Result<()> {
         let round_account = &mut ctx.accounts.round_account;
 
+        let vault_accounts = VaultAccounts {
+            currency: round_account.config.currency,
+            token_mint: ctx.accounts.token_mint.as_deref(),
+            token_program: ctx.accounts.token_program.as_ref(),
+        };
+        let authority_ata = ctx
+            .accounts
+            .authority_ata
+            .as_ref()
+            .map(|authority_ata| authority_ata.to_account_info());
+
         let signer_seeds: &[&[&[u8]]] = &[&[
             b"round",
             &round_account.seed.to_le_bytes(),
             &[round_account.bump],
         ]];
 
+        // unclaimed referral rewards stay in the vault for their referrers
+        let team_vault = ctx.accounts.team_vault.to_account_info();
+        let team_amount = vault_accounts
+            .balance(&team_vault)?
+            .checked_sub(round_account.referral_owed)
+            .ok_or(CustomErrors::MathOverflow)?;
+
         // Team Transfer Ix
-        let transfer_instruction_team = anchor_spl::token::Transfer {
-            from: ctx.accounts.team_vault.to_account_info(),
-            to: ctx.accounts.authority_ata.to_account_info(),
-            authority: round_account.to_account_info(),
-        };
+        vault_accounts.withdraw(
+            &round_account.to_account_info(),
+            signer_seeds,
+            &team_vault,
+            &ctx.accounts.authority.to_account_info(),
+            authority_ata.as_ref(),
+            team_amount,
+        )?;
 
-        let cpi_ctx_team = CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            transfer_instruction_team,
-        )
-        .with_signer(signer_seeds);
+        round_account.team_claimed = true;
+        round_account.update_status();
 
-        anchor_spl::token::transfer(cpi_ctx_team, ctx.accounts.team_vault.amount)?;
+        emit!(TeamClaimed {
+            round: round_account.key(),
+            authority: ctx.accounts.authority.key(),
+            amount: team_amount,
+        });
         Ok(())
     }
 }
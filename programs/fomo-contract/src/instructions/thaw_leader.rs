@@ -0,0 +1,85 @@
+use anchor_lang::{prelude::*, system_program};
+use mpl_core::{
+    instructions::{UpdatePluginV1Cpi, UpdatePluginV1InstructionArgs},
+    types::{FreezeDelegate, Plugin},
+};
+
+use crate::{errors::CustomErrors, events::*, state::*};
+
+#[derive(Accounts)]
+pub struct ThawLeaderContext<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(mut, seeds = [b"round", round_account.seed.to_le_bytes().as_ref()],bump = round_account.bump )]
+    pub round_account: Box<Account<'info, Round>>,
+
+    #[account(seeds = [b"config"], bump = program_config.bump)]
+    pub program_config: Box<Account<'info, ProgramConfig>>,
+
+    /// The asset of the final leader.
+    /// CHECK: address check added
+    #[account(mut, address = round_account.leader_key)]
+    pub asset: UncheckedAccount<'info>,
+
+    /// CHECK: address check added
+    #[account(mut, address = round_account.collection.key())]
+    pub collection: AccountInfo<'info>,
+
+    /// The SPL Noop program.
+    /// CHECK: Checked in mpl-core.
+    pub log_wrapper: Option<AccountInfo<'info>>,
+
+    /// The MPL Core program.
+    /// CHECK: Checked in mpl-core.
+    #[account(address = mpl_core::ID)]
+    pub mpl_core: AccountInfo<'info>,
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+}
+
+impl ThawLeaderContext<'_> {
+    pub fn validate(&self) -> Result<()> {
+        self.round_account.check_not_paused(&self.program_config)?;
+
+        // the leader can win until the prize is claimed, or the round is cancelled
+        require!(
+            self.round_account.can_thaw_leader(),
+            CustomErrors::LeaderKeyLocked
+        );
+        Ok(())
+    }
+
+    #[access_control(ctx.accounts.validate())]
+    pub fn thaw_leader(ctx: Context<ThawLeaderContext>) -> Result<()> {
+        let round_account = &mut ctx.accounts.round_account;
+        round_account.leader_frozen = false;
+
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"round",
+            &round_account.seed.to_le_bytes(),
+            &[round_account.bump],
+        ]];
+
+        UpdatePluginV1Cpi {
+            asset: &ctx.accounts.asset.to_account_info(),
+            __program: &ctx.accounts.mpl_core,
+            payer: &ctx.accounts.authority.to_account_info(),
+            system_program: &ctx.accounts.system_program.to_account_info(),
+            log_wrapper: ctx.accounts.log_wrapper.as_ref(),
+            collection: Some(ctx.accounts.collection.as_ref()),
+            authority: Some(round_account.to_account_info().as_ref()),
+            __args: UpdatePluginV1InstructionArgs {
+                plugin: Plugin::FreezeDelegate(FreezeDelegate { frozen: false }),
+            },
+        }
+        .invoke_signed(signer_seeds)?;
+
+        emit!(LeaderThawed {
+            round: round_account.key(),
+            asset: round_account.leader_key,
+            owner: round_account.last_buyer,
+        });
+        Ok(())
+    }
+}
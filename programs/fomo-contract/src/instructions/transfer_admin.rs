@@ -0,0 +1,26 @@
+use anchor_lang::prelude::*;
+
+use crate::{errors::CustomErrors, events::*, state::*};
+
+#[derive(Accounts)]
+pub struct TransferAdminContext<'info> {
+    #[account(constraint = program_config.admin == authority.key() @ CustomErrors::Unauthorized)]
+    pub authority: Signer<'info>,
+
+    #[account(mut, seeds = [b"config"], bump = program_config.bump)]
+    pub program_config: Box<Account<'info, ProgramConfig>>,
+}
+
+impl TransferAdminContext<'_> {
+    // proposes a new admin, who takes over with accept_admin
+    pub fn transfer_admin(ctx: Context<TransferAdminContext>, new_admin: Pubkey) -> Result<()> {
+        ctx.accounts.program_config.pending_admin = new_admin;
+
+        emit!(AdminTransferred {
+            previous_admin: ctx.accounts.program_config.admin,
+            new_admin,
+            accepted: false,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+
+use crate::{errors::CustomErrors, events::*, state::*};
+
+#[derive(Accounts)]
+pub struct UnpauseRoundContext<'info> {
+    #[account(constraint = round_account.authority == authority.key())]
+    pub authority: Signer<'info>,
+
+    #[account(mut, seeds = [b"round", round_account.seed.to_le_bytes().as_ref()],bump = round_account.bump )]
+    pub round_account: Box<Account<'info, Round>>,
+}
+
+impl UnpauseRoundContext<'_> {
+    pub fn validate(&self) -> Result<()> {
+        require!(self.round_account.paused, CustomErrors::RoundNotPaused);
+        Ok(())
+    }
+
+    #[access_control(ctx.accounts.validate())]
+    pub fn unpause(ctx: Context<UnpauseRoundContext>) -> Result<()> {
+        let round_account = &mut ctx.accounts.round_account;
+        let now = round_account.now()?;
+        round_account.unpause(now);
+
+        emit!(RoundUnpaused {
+            round: round_account.key(),
+            round_close_slot: round_account.round_close_slot,
+        });
+        Ok(())
+    }
+}
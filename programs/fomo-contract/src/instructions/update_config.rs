@@ -0,0 +1,27 @@
+use anchor_lang::prelude::*;
+
+use crate::{errors::CustomErrors, events::*, state::*};
+
+#[derive(Accounts)]
+pub struct UpdateConfigContext<'info> {
+    #[account(constraint = program_config.admin == authority.key() @ CustomErrors::Unauthorized)]
+    pub authority: Signer<'info>,
+
+    #[account(mut, seeds = [b"config"], bump = program_config.bump)]
+    pub program_config: Box<Account<'info, ProgramConfig>>,
+}
+
+impl UpdateConfigContext<'_> {
+    #[access_control(args.validate())]
+    pub fn update_config(ctx: Context<UpdateConfigContext>, args: ProgramConfigArgs) -> Result<()> {
+        let program_config = &mut ctx.accounts.program_config;
+        program_config.update(args)?;
+
+        emit!(ProgramConfigUpdated {
+            admin: program_config.admin,
+            fee_recipient: program_config.fee_recipient,
+            paused: program_config.paused,
+        });
+        Ok(())
+    }
+}
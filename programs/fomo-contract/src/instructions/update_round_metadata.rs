@@ -0,0 +1,102 @@
+use anchor_lang::{prelude::*, system_program};
+use mpl_core::instructions::{UpdateCollectionV1Cpi, UpdateCollectionV1InstructionArgs};
+
+use crate::{errors::CustomErrors, events::*, state::*};
+
+#[derive(Accounts)]
+pub struct UpdateRoundMetadataContext<'info> {
+    #[account(mut, constraint = round_account.authority == authority.key())]
+    pub authority: Signer<'info>,
+
+    #[account(mut, seeds = [b"round", round_account.seed.to_le_bytes().as_ref()],bump = round_account.bump )]
+    pub round_account: Box<Account<'info, Round>>,
+
+    #[account(seeds = [b"config"], bump = program_config.bump)]
+    pub program_config: Box<Account<'info, ProgramConfig>>,
+
+    /// The collection of the round's keys.
+    /// CHECK: address check added
+    #[account(mut, address = round_account.collection.key())]
+    pub collection: AccountInfo<'info>,
+
+    /// The SPL Noop program.
+    /// CHECK: Checked in mpl-core.
+    pub log_wrapper: Option<AccountInfo<'info>>,
+
+    /// The MPL Core program.
+    /// CHECK: Checked in mpl-core.
+    #[account(address = mpl_core::ID)]
+    pub mpl_core: AccountInfo<'info>,
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+}
+
+impl UpdateRoundMetadataContext<'_> {
+    pub fn validate(&self, args: &RoundMetadataArgs) -> Result<()> {
+        self.round_account.check_not_paused(&self.program_config)?;
+
+        require!(
+            matches!(
+                self.round_account.status,
+                RoundStatus::Pending | RoundStatus::Active
+            ),
+            CustomErrors::InvalidRoundStatus
+        );
+
+        // keys already minted keep the metadata they were minted with
+        require_eq!(
+            self.round_account.mint_counter,
+            0,
+            CustomErrors::RoundStarted
+        );
+
+        if let Some(key_metadata) = &args.key_metadata {
+            key_metadata.validate()?;
+        }
+        Ok(())
+    }
+
+    #[access_control(ctx.accounts.validate(&args))]
+    pub fn update_round_metadata(
+        ctx: Context<UpdateRoundMetadataContext>,
+        args: RoundMetadataArgs,
+    ) -> Result<()> {
+        let round_account = &mut ctx.accounts.round_account;
+
+        if let Some(key_metadata) = args.key_metadata {
+            round_account.key_metadata = key_metadata;
+        }
+
+        if args.collection_name.is_some() || args.collection_uri.is_some() {
+            let signer_seeds: &[&[&[u8]]] = &[&[
+                b"round",
+                &round_account.seed.to_le_bytes(),
+                &[round_account.bump],
+            ]];
+
+            UpdateCollectionV1Cpi {
+                collection: &ctx.accounts.collection,
+                payer: &ctx.accounts.authority.to_account_info(),
+                authority: Some(round_account.to_account_info().as_ref()),
+                new_update_authority: None,
+                system_program: &ctx.accounts.system_program.to_account_info(),
+                log_wrapper: ctx.accounts.log_wrapper.as_ref(),
+                __program: &ctx.accounts.mpl_core,
+                __args: UpdateCollectionV1InstructionArgs {
+                    new_name: args.collection_name.clone(),
+                    new_uri: args.collection_uri.clone(),
+                },
+            }
+            .invoke_signed(signer_seeds)?;
+        }
+
+        emit!(RoundMetadataUpdated {
+            round: round_account.key(),
+            collection_name: args.collection_name,
+            collection_uri: args.collection_uri,
+            key_name: round_account.key_metadata.name.clone(),
+            key_base_uri: round_account.key_metadata.base_uri.clone(),
+        });
+        Ok(())
+    }
+}
@@ -1,95 +1,135 @@
 use anchor_lang::{prelude::*, system_program};
-use anchor_spl::token::{Mint, Token, TokenAccount};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 use mpl_core::{
-    accounts::BaseAssetV1,
-    fetch_plugin,
-    types::{UpdateAuthority, VerifiedCreators},
+    instructions::{UpdatePluginV1Cpi, UpdatePluginV1InstructionArgs},
+    types::Plugin,
 };
 
-use crate::{errors::CustomErrors, NftKey, Round};
+use crate::{
+    errors::CustomErrors, events::*, key_attributes, state::*, verify_key_asset, VaultAccounts,
+};
 
 #[derive(Accounts)]
 pub struct WinnerClaimContext<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
 
-    #[account(mut,constraint = authority_ata.mint ==  token_mint.key(), constraint = authority_ata.owner == authority.key())]
-    pub authority_ata: Box<Account<'info, TokenAccount>>,
+    /// The token account of the authority, omitted for SOL rounds.
+    #[account(mut, token::mint = token_mint, token::authority = authority)]
+    pub authority_ata: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
 
     #[account(mut, seeds = [b"round", round_account.seed.to_le_bytes().as_ref()],bump = round_account.bump )]
     pub round_account: Box<Account<'info, Round>>,
 
+    #[account(seeds = [b"config"], bump = program_config.bump)]
+    pub program_config: Box<Account<'info, ProgramConfig>>,
+
     /// CHECK: checking later
     #[account(mut, constraint = asset.key() == key_account.nft_mint.key())]
     pub asset: UncheckedAccount<'info>,
 
     #[account(mut,
-        constraint =  key_account.key_index == round_account.mint_counter,
         seeds = [b"key",round_account.key().as_ref(),key_account.key_index.to_le_bytes().as_ref()],
         bump = key_account.bump
     )]
     pub key_account: Box<Account<'info, NftKey>>,
 
-    pub token_mint: Box<Account<'info, Mint>>,
+    #[account(constraint = token_mint.key() == round_account.token_mint @ CustomErrors::InvalidMint)]
+    pub token_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
 
-    #[account(
+    /// CHECK: the round's treasure vault
+    #[account(mut,
            address = round_account.treasure_vault.key()
     )]
-    pub treasure_vault: Box<Account<'info, TokenAccount>>,
+    pub treasure_vault: UncheckedAccount<'info>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
 
-    pub token_program: Program<'info, Token>,
+    /// The collection to which the asset belongs.
+    /// CHECK: address check added
+    #[account(mut, address = round_account.collection.key())]
+    pub collection: AccountInfo<'info>,
+
+    /// The SPL Noop program.
+    /// CHECK: Checked in mpl-core.
+    pub log_wrapper: Option<AccountInfo<'info>>,
+
+    /// The MPL Core program.
+    /// CHECK: Checked in mpl-core.
+    #[account(address = mpl_core::ID)]
+    pub mpl_core: AccountInfo<'info>,
     #[account(address = system_program::ID)]
     pub system_program: Program<'info, System>,
 }
 
 impl WinnerClaimContext<'_> {
-    pub fn validate(&self) -> Result<()> {
-        let current_slot = Clock::get().unwrap().slot;
+    pub fn validate(&mut self) -> Result<()> {
+        self.round_account.check_not_paused(&self.program_config)?;
+        self.round_account
+            .credit_program_pause(&self.program_config);
+
+        require!(
+            self.round_account.is_claimable(),
+            CustomErrors::InvalidRoundStatus
+        );
+        require!(
+            !self.key_account.prize_claimed,
+            CustomErrors::AlreadyClaimed
+        );
+        require_neq!(self.key_account.exited, 1, CustomErrors::InvalidKeyAccount);
+
+        let now = self.round_account.now()?;
         // check if round is over or not
         require_gt!(
-            current_slot,
+            now,
             self.round_account.round_close_slot,
             CustomErrors::RoundOver
         );
 
-        let asset_data = BaseAssetV1::from_bytes(&self.asset.to_account_info().data.borrow())?;
-
-        if asset_data.update_authority != UpdateAuthority::Collection(self.round_account.collection)
-        {
-            return Err(CustomErrors::InvalidAsset.into());
-        }
-
-        // check for verified creators
-        let attributes_plugin = fetch_plugin::<BaseAssetV1, VerifiedCreators>(
-            &self.asset.to_account_info(),
-            mpl_core::types::PluginType::VerifiedCreators,
-        )
-        .unwrap();
-
-        let is_verified: Option<bool> = match attributes_plugin
-            .1
-            .signatures
-            .iter()
-            .find(|sig| sig.verified && sig.address == self.round_account.key())
-        {
-            Some(_) => Some(true),
-            None => Some(false),
-        };
-
-        match is_verified {
-            Some(true) => {
-                // Proceed with the rest of your code
+        // the current holder is only checked when the prize follows the asset
+        let expected_owner = match self.round_account.config.winner_policy {
+            WinnerPolicy::OriginalBuyer => {
+                require_keys_eq!(
+                    self.key_account.buyer,
+                    self.authority.key(),
+                    CustomErrors::Unauthorized
+                );
+                None
             }
-            _ => {
-                return Err(CustomErrors::InvalidAsset.into());
-            }
-        }
+            WinnerPolicy::CurrentHolder => Some(self.authority.key()),
+        };
+        verify_key_asset(&self.asset, &self.round_account, expected_owner)?;
         Ok(())
     }
 
     #[access_control(ctx.accounts.validate())]
     pub fn winner_claim(ctx: Context<WinnerClaimContext>) -> Result<()> {
         let round_account = &mut ctx.accounts.round_account;
+        let key_account = &mut ctx.accounts.key_account;
+
+        let vault_accounts = VaultAccounts {
+            currency: round_account.config.currency,
+            token_mint: ctx.accounts.token_mint.as_deref(),
+            token_program: ctx.accounts.token_program.as_ref(),
+        };
+
+        // the treasure is split from its balance at the first claim
+        if !round_account.prize_pool_set {
+            round_account.prize_pool =
+                vault_accounts.balance(&ctx.accounts.treasure_vault.to_account_info())?;
+            round_account.prize_pool_set = true;
+        }
+
+        let prize = round_account.prize_for_key(key_account)?;
+        let is_winner = key_account.key_index == round_account.last_key_index;
+        // the winner claims even an empty prize so the round can settle
+        require!(prize > 0 || is_winner, CustomErrors::NoPrize);
+
+        let authority_ata = ctx
+            .accounts
+            .authority_ata
+            .as_ref()
+            .map(|authority_ata| authority_ata.to_account_info());
 
         let signer_seeds: &[&[&[u8]]] = &[&[
             b"round",
@@ -97,19 +137,50 @@ impl WinnerClaimContext<'_> {
             &[round_account.bump],
         ]];
 
-        let transfer_instruction_burn = anchor_spl::token::Transfer {
-            from: ctx.accounts.treasure_vault.to_account_info(),
-            to: ctx.accounts.authority_ata.to_account_info(),
-            authority: round_account.to_account_info(),
-        };
-
-        let cpi_ctx_burn = CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            transfer_instruction_burn,
-        )
-        .with_signer(signer_seeds);
+        if prize > 0 {
+            vault_accounts.withdraw(
+                &round_account.to_account_info(),
+                signer_seeds,
+                &ctx.accounts.treasure_vault.to_account_info(),
+                &ctx.accounts.authority.to_account_info(),
+                authority_ata.as_ref(),
+                prize,
+            )?;
+        }
 
-        anchor_spl::token::transfer(cpi_ctx_burn, ctx.accounts.treasure_vault.amount)?;
+        key_account.prize_claimed = true;
+        if is_winner {
+            round_account.winner = ctx.accounts.authority.key();
+            round_account.winner_claimed = true;
+        }
+        round_account.update_status();
+
+        // show the new state of the key on the asset
+        UpdatePluginV1Cpi {
+            asset: &ctx.accounts.asset.to_account_info(),
+            __program: &ctx.accounts.mpl_core,
+            payer: &ctx.accounts.authority.to_account_info(),
+            system_program: &ctx.accounts.system_program.to_account_info(),
+            log_wrapper: ctx.accounts.log_wrapper.as_ref(),
+            collection: Some(ctx.accounts.collection.as_ref()),
+            authority: Some(round_account.to_account_info().as_ref()),
+            __args: UpdatePluginV1InstructionArgs {
+                plugin: Plugin::Attributes(key_attributes(
+                    round_account.seed,
+                    key_account,
+                    is_winner,
+                )),
+            },
+        }
+        .invoke_signed(signer_seeds)?;
+
+        emit!(WinnerClaimed {
+            round: round_account.key(),
+            claimer: ctx.accounts.authority.key(),
+            key_index: key_account.key_index,
+            prize,
+            is_winner,
+        });
 
         Ok(())
     }
@@ -1,12 +1,14 @@
-mod state;
+pub mod state;
 use state::*;
 mod utils;
 use utils::*;
+mod vault;
+use vault::*;
 mod instructions;
 use instructions::*;
 mod errors;
+pub mod events;
 use anchor_lang::prelude::*;
-use mpl_core::instructions::*;
 
 declare_id!("BXPuyjuKMVtYMdiHumY42cSF7vGWVX2sEyP1jSfBbwR2");
 
@@ -15,35 +17,104 @@ pub mod fomo_contract {
 
     use super::*;
 
+    pub fn initialize_config(
+        ctx: Context<InitializeConfigContext>,
+        args: ProgramConfigArgs,
+    ) -> Result<()> {
+        InitializeConfigContext::initialize_config(ctx, args)?;
+        Ok(())
+    }
+    pub fn update_config(ctx: Context<UpdateConfigContext>, args: ProgramConfigArgs) -> Result<()> {
+        UpdateConfigContext::update_config(ctx, args)?;
+        Ok(())
+    }
+    pub fn transfer_admin(ctx: Context<TransferAdminContext>, new_admin: Pubkey) -> Result<()> {
+        TransferAdminContext::transfer_admin(ctx, new_admin)?;
+        Ok(())
+    }
+    pub fn accept_admin(ctx: Context<AcceptAdminContext>) -> Result<()> {
+        AcceptAdminContext::accept_admin(ctx)?;
+        Ok(())
+    }
+
     pub fn create_round(
         ctx: Context<CreateRoundContext>,
         seed: u64,
         name: String,
         uri: String,
+        key_metadata: KeyMetadata,
+        config: RoundConfig,
     ) -> Result<()> {
-        CreateRoundContext::create_round(ctx, seed, name, uri)?;
+        CreateRoundContext::create_round(ctx, seed, name, uri, key_metadata, config)?;
         Ok(())
     }
     pub fn create_reserves(ctx: Context<CreateRoundReservesContext>) -> Result<()> {
         CreateRoundReservesContext::create_reserves(ctx)?;
         Ok(())
     }
+    pub fn update_round_metadata(
+        ctx: Context<UpdateRoundMetadataContext>,
+        args: RoundMetadataArgs,
+    ) -> Result<()> {
+        UpdateRoundMetadataContext::update_round_metadata(ctx, args)?;
+        Ok(())
+    }
 
     pub fn create_key(ctx: Context<CreateKeyContext>) -> Result<()> {
-        CreateKeyContext::create_key(ctx)?;
+        CreateKeyContext::create_key(ctx, 1)?;
+        Ok(())
+    }
+    pub fn create_keys(ctx: Context<CreateKeyContext>, count: u64) -> Result<()> {
+        CreateKeyContext::create_key(ctx, count)?;
+        Ok(())
+    }
+    pub fn create_referrer(ctx: Context<CreateReferrerContext>) -> Result<()> {
+        CreateReferrerContext::create_referrer(ctx)?;
+        Ok(())
+    }
+    pub fn claim_referral(ctx: Context<ClaimReferralContext>) -> Result<()> {
+        ClaimReferralContext::claim_referral(ctx)?;
         Ok(())
     }
     pub fn burn_key(ctx: Context<BurnKeyContext>) -> Result<()> {
         BurnKeyContext::burn(ctx)?;
         Ok(())
     }
+    pub fn claim_dividends(ctx: Context<ClaimDividendsContext>) -> Result<()> {
+        ClaimDividendsContext::claim_dividends(ctx)?;
+        Ok(())
+    }
 
     pub fn winner_claim(ctx: Context<WinnerClaimContext>) -> Result<()> {
         WinnerClaimContext::winner_claim(ctx)?;
         Ok(())
     }
+    pub fn thaw_leader(ctx: Context<ThawLeaderContext>) -> Result<()> {
+        ThawLeaderContext::thaw_leader(ctx)?;
+        Ok(())
+    }
     pub fn claim_round(ctx: Context<RoundClaimContext>) -> Result<()> {
         RoundClaimContext::claim(ctx)?;
         Ok(())
     }
+    pub fn rollover_round(ctx: Context<RolloverRoundContext>) -> Result<()> {
+        RolloverRoundContext::rollover(ctx)?;
+        Ok(())
+    }
+    pub fn pause_round(ctx: Context<PauseRoundContext>) -> Result<()> {
+        PauseRoundContext::pause(ctx)?;
+        Ok(())
+    }
+    pub fn unpause_round(ctx: Context<UnpauseRoundContext>) -> Result<()> {
+        UnpauseRoundContext::unpause(ctx)?;
+        Ok(())
+    }
+    pub fn cancel_round(ctx: Context<CancelRoundContext>) -> Result<()> {
+        CancelRoundContext::cancel(ctx)?;
+        Ok(())
+    }
+    pub fn refund_key(ctx: Context<RefundKeyContext>) -> Result<()> {
+        RefundKeyContext::refund(ctx)?;
+        Ok(())
+    }
 }
@@ -0,0 +1,190 @@
+use anchor_lang::prelude::*;
+
+use crate::{errors::CustomErrors, BASIS_POINTS_DENOMINATOR};
+
+// fixed point scale used for exponential growth
+const CURVE_PRECISION: u128 = 1_000_000_000_000;
+
+// prices are set in 10^-price_decimals tokens (RoundConfig::price_decimals)
+// and scaled to base units of the token on-chain
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub enum PriceCurve {
+    // base_price + price_step * (key_index - 1)
+    Linear {
+        base_price: u64,
+        price_step: u64,
+    },
+    // base_price grown by growth_basis_points for every key after the first
+    Exponential {
+        base_price: u64,
+        growth_basis_points: u16,
+    },
+    // base_price + price_step for every full tier of keys_per_tier keys
+    Stepped {
+        base_price: u64,
+        price_step: u64,
+        keys_per_tier: u64,
+    },
+    // same price for every key
+    Constant {
+        price: u64,
+    },
+}
+
+// free keys are rejected by validate, the default only fills empty accounts
+impl Default for PriceCurve {
+    fn default() -> Self {
+        PriceCurve::Constant { price: 0 }
+    }
+}
+
+impl PriceCurve {
+    pub fn validate(&self) -> Result<()> {
+        if let PriceCurve::Stepped { keys_per_tier, .. } = self {
+            require_gt!(*keys_per_tier, 0, CustomErrors::InvalidRoundConfig);
+        }
+        // every key must cost something, free keys would extend the timer for nothing
+        let first_price = match *self {
+            PriceCurve::Linear { base_price, .. }
+            | PriceCurve::Exponential { base_price, .. }
+            | PriceCurve::Stepped { base_price, .. } => base_price,
+            PriceCurve::Constant { price } => price,
+        };
+        require_gt!(first_price, 0, CustomErrors::InvalidRoundConfig);
+        Ok(())
+    }
+
+    // price of the key at `key_index` (starting at 1) in base units, with the
+    // curve prices scaled up by `decimals` places, None on overflow.
+    // Pure so that clients can quote the exact on-chain price.
+    pub fn price_for_index(&self, key_index: u64, decimals: u8) -> Option<u64> {
+        let keys_before = key_index.checked_sub(1)?;
+        let scale = 10u64.checked_pow(u32::from(decimals))?;
+
+        let price = match *self {
+            PriceCurve::Linear {
+                base_price,
+                price_step,
+            } => price_step
+                .checked_mul(keys_before)?
+                .checked_add(base_price)?,
+            PriceCurve::Exponential {
+                base_price,
+                growth_basis_points,
+            } => {
+                // scaled before the growth so fractions of a token are kept
+                let growth = pow_basis_points(growth_basis_points, keys_before)?;
+                let price = u128::from(base_price)
+                    .checked_mul(u128::from(scale))?
+                    .checked_mul(growth)?
+                    .checked_div(CURVE_PRECISION)?;
+                return u64::try_from(price).ok();
+            }
+            PriceCurve::Stepped {
+                base_price,
+                price_step,
+                keys_per_tier,
+            } => price_step
+                .checked_mul(keys_before.checked_div(keys_per_tier)?)?
+                .checked_add(base_price)?,
+            PriceCurve::Constant { price } => price,
+        };
+        price.checked_mul(scale)
+    }
+}
+
+// (1 + basis_points / 10000) ^ exponent, scaled by CURVE_PRECISION
+fn pow_basis_points(basis_points: u16, mut exponent: u64) -> Option<u128> {
+    let denominator = u128::from(BASIS_POINTS_DENOMINATOR);
+    let mut base = denominator
+        .checked_add(u128::from(basis_points))?
+        .checked_mul(CURVE_PRECISION)?
+        .checked_div(denominator)?;
+    let mut result = CURVE_PRECISION;
+
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = result.checked_mul(base)?.checked_div(CURVE_PRECISION)?;
+        }
+        exponent >>= 1;
+        if exponent > 0 {
+            base = base.checked_mul(base)?.checked_div(CURVE_PRECISION)?;
+        }
+    }
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn price_for_index_follows_each_curve() {
+        let linear = PriceCurve::Linear {
+            base_price: 10,
+            price_step: 5,
+        };
+        assert_eq!(linear.price_for_index(1, 0), Some(10));
+        assert_eq!(linear.price_for_index(3, 0), Some(20));
+        // scaled to base units of a 6 decimal token
+        assert_eq!(linear.price_for_index(3, 6), Some(20_000_000));
+
+        let stepped = PriceCurve::Stepped {
+            base_price: 10,
+            price_step: 10,
+            keys_per_tier: 100,
+        };
+        assert_eq!(stepped.price_for_index(100, 0), Some(10));
+        assert_eq!(stepped.price_for_index(101, 0), Some(20));
+
+        let constant = PriceCurve::Constant { price: 7 };
+        assert_eq!(constant.price_for_index(1_000, 2), Some(700));
+
+        // 10% growth per key, fractions of a token are kept
+        let exponential = PriceCurve::Exponential {
+            base_price: 1,
+            growth_basis_points: 1000,
+        };
+        assert_eq!(exponential.price_for_index(1, 6), Some(1_000_000));
+        assert_eq!(exponential.price_for_index(3, 6), Some(1_210_000));
+    }
+
+    #[test]
+    fn price_for_index_rejects_key_zero_and_overflow() {
+        let constant = PriceCurve::Constant { price: 1 };
+        assert_eq!(constant.price_for_index(0, 0), None);
+
+        let linear = PriceCurve::Linear {
+            base_price: 1,
+            price_step: u64::MAX,
+        };
+        assert_eq!(linear.price_for_index(3, 0), None);
+        assert_eq!(constant.price_for_index(1, 20), None);
+    }
+
+    #[test]
+    fn pow_basis_points_compounds_growth() {
+        assert_eq!(pow_basis_points(500, 0), Some(CURVE_PRECISION));
+        assert_eq!(pow_basis_points(10_000, 1), Some(2 * CURVE_PRECISION));
+        assert_eq!(pow_basis_points(500, 2), Some(1_102_500_000_000));
+        assert_eq!(pow_basis_points(10_000, 10), Some(1024 * CURVE_PRECISION));
+        assert_eq!(pow_basis_points(10_000, 1_000), None);
+    }
+
+    #[test]
+    fn validate_rejects_free_keys() {
+        assert!(PriceCurve::Constant { price: 0 }.validate().is_err());
+        assert!(PriceCurve::Linear {
+            base_price: 0,
+            price_step: 10,
+        }
+        .validate()
+        .is_err());
+        assert!(PriceCurve::Linear {
+            base_price: 10,
+            price_step: 0,
+        }
+        .validate()
+        .is_ok());
+    }
+}
@@ -1,24 +1,80 @@
 use anchor_lang::prelude::*;
 
+use crate::{errors::CustomErrors, POOL_ACC_PRECISION};
+
 #[account]
 #[derive(Default, InitSpace)]
 pub struct NftKey {
     pub nft_mint: Pubkey,
+    // index of the first key in this purchase
     pub key_index: u64,
     pub exited: u8,
     pub bump: u8,
+    // keys bought in this purchase, held by a single asset
+    pub quantity: u64,
+    // pool earnings already accounted for, scaled by POOL_ACC_PRECISION
+    pub reward_debt: u128,
+    // pool earnings paid out to this key so far
+    pub dividends_claimed: u64,
+    // treasure prize paid out to this key
+    pub prize_claimed: bool,
+    // amount charged for this purchase, the sum of its rounded down fee shares
+    pub price_paid: u64,
+    // slot the asset was minted at
+    pub mint_slot: u64,
+    // wallet that bought this key, the asset may have moved since
+    pub buyer: Pubkey,
 }
 
 pub struct CreateKeyArgs {
     pub nft_mint: Pubkey,
     pub key_index: u64,
     pub bump: u8,
+    pub quantity: u64,
+    pub pool_per_key_acc: u128,
+    pub price_paid: u64,
+    pub mint_slot: u64,
+    pub buyer: Pubkey,
 }
 impl NftKey {
-    pub fn create(&mut self, args: CreateKeyArgs) {
+    pub fn create(&mut self, args: CreateKeyArgs) -> Result<()> {
         self.nft_mint = args.nft_mint;
         self.bump = args.bump;
         self.key_index = args.key_index;
         self.exited = 0;
+        self.quantity = args.quantity;
+        self.reward_debt = self.accrued(args.pool_per_key_acc)?;
+        self.dividends_claimed = 0;
+        self.prize_claimed = false;
+        self.price_paid = args.price_paid;
+        self.mint_slot = args.mint_slot;
+        self.buyer = args.buyer;
+        Ok(())
+    }
+
+    // index of the last key in this purchase
+    pub fn last_index(&self) -> Result<u64> {
+        Ok(self
+            .key_index
+            .checked_add(self.quantity)
+            .and_then(|end| end.checked_sub(1))
+            .ok_or(CustomErrors::MathOverflow)?)
+    }
+
+    // pool earnings of this key since the round started, scaled by POOL_ACC_PRECISION
+    pub fn accrued(&self, pool_per_key_acc: u128) -> Result<u128> {
+        Ok(pool_per_key_acc
+            .checked_mul(u128::from(self.quantity))
+            .ok_or(CustomErrors::MathOverflow)?)
+    }
+
+    // pool earnings not paid out yet
+    pub fn pending_dividends(&self, pool_per_key_acc: u128) -> Result<u64> {
+        let pending = self
+            .accrued(pool_per_key_acc)?
+            .checked_sub(self.reward_debt)
+            .ok_or(CustomErrors::MathOverflow)?
+            / POOL_ACC_PRECISION;
+        Ok(u64::try_from(pending).map_err(|_| CustomErrors::MathOverflow)?)
     }
 }
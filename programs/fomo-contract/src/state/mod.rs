@@ -1,5 +1,11 @@
+pub mod curve;
 pub mod key;
+pub mod program_config;
+pub mod referrer;
 pub mod round;
 
+pub use curve::*;
 pub use key::*;
+pub use program_config::*;
+pub use referrer::*;
 pub use round::*;
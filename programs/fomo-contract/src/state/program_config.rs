@@ -0,0 +1,94 @@
+use anchor_lang::prelude::*;
+
+use crate::{errors::CustomErrors, state::TimerMode};
+
+pub const MAX_ALLOWED_MINTS: usize = 10;
+
+#[account]
+#[derive(Default, InitSpace)]
+pub struct ProgramConfig {
+    // super admin of the program
+    pub admin: Pubkey,
+    // admin proposed by transfer_admin, until accepted
+    pub pending_admin: Pubkey,
+    // token mints rounds can be created with
+    #[max_len(MAX_ALLOWED_MINTS)]
+    pub allowed_mints: Vec<Pubkey>,
+    // receives protocol fees
+    pub fee_recipient: Pubkey,
+    // stops every round of the program, the paused time is added back to the
+    // round timers the next time each round is used
+    pub paused: bool,
+    pub bump: u8,
+    // when the program was paused, in both timer modes
+    pub paused_at_slot: u64,
+    pub paused_at_timestamp: u64,
+    // total time the program was paused, in both timer modes
+    pub paused_slots: u64,
+    pub paused_seconds: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ProgramConfigArgs {
+    pub allowed_mints: Vec<Pubkey>,
+    pub fee_recipient: Pubkey,
+    pub paused: bool,
+}
+
+impl ProgramConfigArgs {
+    pub fn validate(&self) -> Result<()> {
+        require_gte!(
+            MAX_ALLOWED_MINTS,
+            self.allowed_mints.len(),
+            CustomErrors::TooManyMints
+        );
+        Ok(())
+    }
+}
+
+impl ProgramConfig {
+    pub fn create(&mut self, admin: Pubkey, bump: u8, args: ProgramConfigArgs) -> Result<()> {
+        self.admin = admin;
+        self.pending_admin = Pubkey::default();
+        self.bump = bump;
+        self.paused = false;
+        self.paused_slots = 0;
+        self.paused_seconds = 0;
+        self.update(args)
+    }
+
+    pub fn update(&mut self, args: ProgramConfigArgs) -> Result<()> {
+        self.allowed_mints = args.allowed_mints;
+        self.fee_recipient = args.fee_recipient;
+
+        let clock = Clock::get()?;
+        let timestamp =
+            u64::try_from(clock.unix_timestamp).map_err(|_| CustomErrors::MathOverflow)?;
+        if args.paused && !self.paused {
+            self.paused_at_slot = clock.slot;
+            self.paused_at_timestamp = timestamp;
+        }
+        if !args.paused && self.paused {
+            self.paused_slots = self
+                .paused_slots
+                .saturating_add(clock.slot.saturating_sub(self.paused_at_slot));
+            self.paused_seconds = self
+                .paused_seconds
+                .saturating_add(timestamp.saturating_sub(self.paused_at_timestamp));
+        }
+        self.paused = args.paused;
+        Ok(())
+    }
+
+    // total time the program was paused, in the units of `timer_mode`
+    pub fn paused_time(&self, timer_mode: TimerMode) -> u64 {
+        match timer_mode {
+            TimerMode::Slot => self.paused_slots,
+            TimerMode::UnixTimestamp => self.paused_seconds,
+        }
+    }
+
+    pub fn is_mint_allowed(&self, mint: &Pubkey) -> bool {
+        self.allowed_mints.contains(mint)
+    }
+}
@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::CustomErrors;
+
+#[account]
+#[derive(Default, InitSpace)]
+pub struct Referrer {
+    pub round: Pubkey,
+    // wallet credited for the referrals
+    pub authority: Pubkey,
+    // total rewards earned in the round
+    pub rewards: u64,
+    // rewards already paid out
+    pub claimed: u64,
+    // purchases made with this referrer
+    pub referral_count: u64,
+    pub bump: u8,
+}
+
+pub struct CreateReferrerArgs {
+    pub round: Pubkey,
+    pub authority: Pubkey,
+    pub bump: u8,
+}
+
+impl Referrer {
+    pub fn create(&mut self, args: CreateReferrerArgs) {
+        self.round = args.round;
+        self.authority = args.authority;
+        self.bump = args.bump;
+        self.rewards = 0;
+        self.claimed = 0;
+        self.referral_count = 0;
+    }
+
+    pub fn add_referral(&mut self, amount: u64) -> Result<()> {
+        self.rewards = self
+            .rewards
+            .checked_add(amount)
+            .ok_or(CustomErrors::MathOverflow)?;
+        self.referral_count = self
+            .referral_count
+            .checked_add(1)
+            .ok_or(CustomErrors::MathOverflow)?;
+        Ok(())
+    }
+
+    // rewards not paid out yet
+    pub fn unclaimed(&self) -> Result<u64> {
+        Ok(self
+            .rewards
+            .checked_sub(self.claimed)
+            .ok_or(CustomErrors::MathOverflow)?)
+    }
+}
@@ -1,5 +1,212 @@
 use anchor_lang::prelude::*;
 
+use crate::{
+    basis_points_of,
+    errors::CustomErrors,
+    state::{NftKey, PriceCurve, ProgramConfig},
+    BASIS_POINTS_DENOMINATOR, POOL_ACC_PRECISION,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq, InitSpace)]
+pub enum TimerMode {
+    // timers count slots
+    #[default]
+    Slot,
+    // timers count seconds of the cluster unix timestamp
+    UnixTimestamp,
+}
+
+impl TimerMode {
+    // current time in the units of this mode
+    pub fn now(&self) -> Result<u64> {
+        let clock = Clock::get()?;
+        match self {
+            TimerMode::Slot => Ok(clock.slot),
+            TimerMode::UnixTimestamp => {
+                Ok(u64::try_from(clock.unix_timestamp).map_err(|_| CustomErrors::MathOverflow)?)
+            }
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq, InitSpace)]
+pub enum Currency {
+    // keys are paid in an SPL or Token-2022 token, vaults are token accounts
+    #[default]
+    Token,
+    // keys are paid in lamports, vaults are program owned accounts
+    Sol,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq, InitSpace)]
+pub enum WinnerPolicy {
+    // prizes go to the wallet that bought the key
+    #[default]
+    OriginalBuyer,
+    // prizes go to whoever holds the key asset at claim time
+    CurrentHolder,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct PrizeTable {
+    // share of the treasure for the last key bought
+    pub winner_basis_points: u16,
+    // keys bought right before the last one that share runner_up_basis_points
+    pub runner_up_count: u64,
+    pub runner_up_basis_points: u16,
+    // share left in the treasure vault for a follow-up round
+    pub rollover_basis_points: u16,
+}
+
+impl PrizeTable {
+    pub fn validate(&self) -> Result<()> {
+        let total_basis_points = u64::from(self.winner_basis_points)
+            + u64::from(self.runner_up_basis_points)
+            + u64::from(self.rollover_basis_points);
+
+        require_eq!(
+            total_basis_points,
+            BASIS_POINTS_DENOMINATOR,
+            CustomErrors::InvalidFeeSplit
+        );
+        require!(
+            self.runner_up_basis_points == 0 || self.runner_up_count > 0,
+            CustomErrors::InvalidRoundConfig
+        );
+        // the winner claim settles the round, it must pay something
+        require_gt!(
+            self.winner_basis_points,
+            0,
+            CustomErrors::InvalidRoundConfig
+        );
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct RoundConfig {
+    // fee split of every key purchase, must sum to 10_000
+    pub team_fee_basis_points: u16,
+    // part of the team fee paid to the referrer of a purchase
+    pub referral_fee_basis_points: u16,
+    pub pool_fee_basis_points: u16,
+    pub burn_fee_basis_points: u16,
+    pub treasure_fee_basis_points: u16,
+    // unit of every timer below and of round_close_slot
+    pub timer_mode: TimerMode,
+    // maximum time the round can stay open ahead of now
+    pub timer_window: u64,
+    // time added to the remaining time for every key bought
+    pub timer_increment: u64,
+    // no keys can be bought from this time on, 0 for no hard end
+    pub round_hard_end_slot: u64,
+    // key pricing, in 10^-price_decimals tokens
+    pub price_curve: PriceCurve,
+    // decimals of the token, must match the mint, 9 for SOL rounds
+    pub token_decimals: u8,
+    // decimals of the curve prices, at most token_decimals. 0 prices keys in
+    // whole tokens, 3 in thousandths of a token (0.001 SOL for SOL rounds)
+    pub price_decimals: u8,
+    // what keys are paid in
+    pub currency: Currency,
+    // split of the treasure once the round ends
+    pub prize_table: PrizeTable,
+    // who is paid when a prize is claimed
+    pub winner_policy: WinnerPolicy,
+    // the round can be cancelled while fewer keys than this were sold
+    pub cancel_key_threshold: u64,
+}
+
+impl RoundConfig {
+    pub fn validate(&self) -> Result<()> {
+        let total_basis_points = u64::from(self.team_fee_basis_points)
+            + u64::from(self.pool_fee_basis_points)
+            + u64::from(self.burn_fee_basis_points)
+            + u64::from(self.treasure_fee_basis_points);
+
+        require_eq!(
+            total_basis_points,
+            BASIS_POINTS_DENOMINATOR,
+            CustomErrors::InvalidFeeSplit
+        );
+        require_gte!(
+            self.team_fee_basis_points,
+            self.referral_fee_basis_points,
+            CustomErrors::InvalidFeeSplit
+        );
+        require_gt!(self.timer_window, 0, CustomErrors::InvalidRoundConfig);
+        require!(
+            self.timer_increment > 0 && self.timer_increment <= self.timer_window,
+            CustomErrors::InvalidRoundConfig
+        );
+        require_gte!(
+            self.token_decimals,
+            self.price_decimals,
+            CustomErrors::InvalidRoundConfig
+        );
+        self.prize_table.validate()?;
+        self.price_curve.validate()
+    }
+}
+
+pub const MAX_KEY_NAME_LEN: usize = 24;
+pub const MAX_KEY_BASE_URI_LEN: usize = 160;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, InitSpace)]
+pub struct KeyMetadata {
+    // key assets are named "{name} #{key_index}"
+    #[max_len(MAX_KEY_NAME_LEN)]
+    pub name: String,
+    // key assets point to "{base_uri}/{key_index}.json"
+    #[max_len(MAX_KEY_BASE_URI_LEN)]
+    pub base_uri: String,
+}
+
+impl KeyMetadata {
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            !self.name.is_empty() && self.name.len() <= MAX_KEY_NAME_LEN,
+            CustomErrors::InvalidKeyMetadata
+        );
+        require!(
+            !self.base_uri.is_empty() && self.base_uri.len() <= MAX_KEY_BASE_URI_LEN,
+            CustomErrors::InvalidKeyMetadata
+        );
+        Ok(())
+    }
+
+    pub fn asset_name(&self, key_index: u64) -> String {
+        format!("{} #{}", self.name, key_index)
+    }
+
+    pub fn asset_uri(&self, key_index: u64) -> String {
+        format!("{}/{}.json", self.base_uri.trim_end_matches('/'), key_index)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct RoundMetadataArgs {
+    // collection fields left as None are not changed
+    pub collection_name: Option<String>,
+    pub collection_uri: Option<String>,
+    pub key_metadata: Option<KeyMetadata>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq, InitSpace)]
+pub enum RoundStatus {
+    // created, reserves not set up yet
+    #[default]
+    Pending,
+    // keys can be bought
+    Active,
+    // timer ran out, prizes can be claimed
+    Ended,
+    // winner and team have claimed
+    Settled,
+    // stopped by the authority
+    Cancelled,
+}
+
 #[account]
 #[derive(Default, InitSpace)]
 pub struct Round {
@@ -11,13 +218,57 @@ pub struct Round {
     pub mint_counter: u64,
     // counter for nft burned
     pub nft_burn_counter: u64,
-    // counter for 24 hours
+    // counter for 24 hours, in config.timer_mode units
     pub round_close_slot: u64,
     pub team_vault: Pubkey,
     pub pool_vault: Pubkey,
     pub treasure_vault: Pubkey,
     pub collection: Pubkey,
+    // mint keys are paid in, the native mint for SOL rounds
+    pub token_mint: Pubkey,
     pub bump: u8,
+    // economics of the round, fixed at creation
+    pub config: RoundConfig,
+    // total tokens burned from key purchases
+    pub burned_amount: u64,
+    // key_index of the latest purchase, 0 before the first key
+    pub last_key_index: u64,
+    pub status: RoundStatus,
+    // who claimed the treasure
+    pub winner: Pubkey,
+    pub winner_claimed: bool,
+    pub team_claimed: bool,
+    // pool fees earned per live key since the round started, scaled by POOL_ACC_PRECISION
+    pub pool_per_key_acc: u128,
+    // pool fees paid while no key was live, owed to nobody
+    pub pool_unallocated: u64,
+    // referral rewards held in the team vault and not claimed yet
+    pub referral_owed: u64,
+    // treasure balance when the first prize was claimed, split by config.prize_table
+    pub prize_pool: u64,
+    // set once prize_pool is taken, an empty treasure still counts
+    pub prize_pool_set: bool,
+    // rounds linked by rollover_round
+    pub previous_round: Pubkey,
+    pub next_round: Pubkey,
+    // price paid for the keys not burned yet, refunded if the round is cancelled
+    pub refundable_amount: u64,
+    // transfer fees the treasure pays on top of refundable_amount to refund every key
+    pub refund_fees: u64,
+    // stops every instruction of the round but cancel_round and unpause_round
+    pub paused: bool,
+    // when the round was paused, the paused time is added back to the timers on unpause
+    pub paused_at: u64,
+    // program pause time already added to the timers, see credit_program_pause
+    pub program_paused_time: u64,
+    // name and uri template of the key assets
+    pub key_metadata: KeyMetadata,
+    // asset of the latest purchase, frozen while it can still win
+    pub leader_key: Pubkey,
+    // buyer of the leader key
+    pub last_buyer: Pubkey,
+    // cleared by thaw_leader once the winner has claimed
+    pub leader_frozen: bool,
 }
 
 pub struct RoundCreateArgs {
@@ -29,6 +280,10 @@ pub struct RoundCreateArgs {
     pub pool_vault: Pubkey,
     pub treasure_vault: Pubkey,
     pub collection: Pubkey,
+    pub token_mint: Pubkey,
+    pub config: RoundConfig,
+    pub key_metadata: KeyMetadata,
+    pub program_paused_time: u64,
 }
 pub struct RoundCreateReservesArgs {
     pub pool_vault: Pubkey,
@@ -47,9 +302,261 @@ impl Round {
         self.round_close_slot = args.round_close_slot;
         self.nft_burn_counter = 0;
         self.collection = args.collection;
+        self.token_mint = args.token_mint;
+        self.config = args.config;
+        self.burned_amount = 0;
+        self.last_key_index = 0;
+        self.status = RoundStatus::Pending;
+        self.winner = Pubkey::default();
+        self.winner_claimed = false;
+        self.team_claimed = false;
+        self.pool_per_key_acc = 0;
+        self.pool_unallocated = 0;
+        self.referral_owed = 0;
+        self.prize_pool = 0;
+        self.prize_pool_set = false;
+        self.previous_round = Pubkey::default();
+        self.next_round = Pubkey::default();
+        self.refundable_amount = 0;
+        self.refund_fees = 0;
+        self.paused = false;
+        self.paused_at = 0;
+        self.program_paused_time = args.program_paused_time;
+        self.key_metadata = args.key_metadata;
+        self.leader_key = Pubkey::default();
+        self.last_buyer = Pubkey::default();
+        self.leader_frozen = false;
     }
     pub fn create_reserves(&mut self, args: RoundCreateReservesArgs) {
         self.pool_vault = args.pool_vault;
         self.treasure_vault = args.treasure_vault;
+        self.status = RoundStatus::Active;
+    }
+
+    // the round and the whole program must both be running
+    pub fn check_not_paused(&self, program_config: &ProgramConfig) -> Result<()> {
+        require!(!program_config.paused, CustomErrors::ProgramPaused);
+        require!(!self.paused, CustomErrors::RoundPaused);
+        Ok(())
+    }
+
+    pub fn pause(&mut self, now: u64) {
+        self.paused = true;
+        self.paused_at = now;
+    }
+
+    // buyers get back the time the round was paused, hard end included
+    pub fn unpause(&mut self, now: u64) {
+        self.add_paused_time(now.saturating_sub(self.paused_at));
+        self.paused = false;
+        self.paused_at = 0;
+    }
+
+    // gives buyers back the time the whole program was paused since the last call,
+    // call before reading the timers
+    pub fn credit_program_pause(&mut self, program_config: &ProgramConfig) {
+        let program_paused_time = program_config.paused_time(self.config.timer_mode);
+        self.add_paused_time(program_paused_time.saturating_sub(self.program_paused_time));
+        self.program_paused_time = program_paused_time;
+    }
+
+    fn add_paused_time(&mut self, paused_for: u64) {
+        self.round_close_slot = self.round_close_slot.saturating_add(paused_for);
+        if self.config.round_hard_end_slot != 0 {
+            self.config.round_hard_end_slot =
+                self.config.round_hard_end_slot.saturating_add(paused_for);
+        }
+    }
+
+    // the new key is minted frozen and takes over from the previous leader
+    pub fn set_leader(&mut self, leader_key: Pubkey, last_buyer: Pubkey) {
+        self.leader_key = leader_key;
+        self.last_buyer = last_buyer;
+        self.leader_frozen = true;
+    }
+
+    // the leader stays frozen until the winner has claimed or the round is cancelled
+    pub fn can_thaw_leader(&self) -> bool {
+        self.leader_frozen && (self.winner_claimed || self.status == RoundStatus::Cancelled)
+    }
+
+    // a round can be claimed from once its timer ran out
+    pub fn is_claimable(&self) -> bool {
+        matches!(
+            self.status,
+            RoundStatus::Active | RoundStatus::Ended | RoundStatus::Settled
+        )
+    }
+
+    // records the end of an active round, settles it once both claims are done.
+    // A round without keys has no winner and settles with the team claim.
+    pub fn update_status(&mut self) {
+        if self.status == RoundStatus::Active {
+            self.status = RoundStatus::Ended;
+        }
+        let winner_done = self.winner_claimed || self.mint_counter == 0;
+        if self.status == RoundStatus::Ended && winner_done && self.team_claimed {
+            self.status = RoundStatus::Settled;
+        }
+    }
+
+    // keys minted and not burned yet
+    pub fn live_keys(&self) -> Result<u64> {
+        Ok(self
+            .mint_counter
+            .checked_sub(self.nft_burn_counter)
+            .ok_or(CustomErrors::MathOverflow)?)
+    }
+
+    // credits pool fees to the live keys, call before adding the buyer's keys
+    pub fn distribute_pool(&mut self, amount: u64) -> Result<()> {
+        let live_keys = self.live_keys()?;
+        if live_keys == 0 {
+            self.pool_unallocated = self
+                .pool_unallocated
+                .checked_add(amount)
+                .ok_or(CustomErrors::MathOverflow)?;
+            return Ok(());
+        }
+
+        let per_key = u128::from(amount)
+            .checked_mul(POOL_ACC_PRECISION)
+            .ok_or(CustomErrors::MathOverflow)?
+            / u128::from(live_keys);
+        self.pool_per_key_acc = self
+            .pool_per_key_acc
+            .checked_add(per_key)
+            .ok_or(CustomErrors::MathOverflow)?;
+        Ok(())
+    }
+
+    // prize of a key from prize_pool, 0 if the key is not eligible
+    pub fn prize_for_key(&self, key_account: &NftKey) -> Result<u64> {
+        let prize_table = self.config.prize_table;
+        let mut prize = 0;
+
+        // the purchase holding the last key won
+        if key_account.key_index == self.last_key_index {
+            prize = basis_points_of(self.prize_pool, prize_table.winner_basis_points)?;
+        }
+
+        // the runner_up_count keys before the last purchase share their pool equally,
+        // the other keys of a winning batch are not runner ups
+        let window_end = self.last_key_index.saturating_sub(1);
+        let window_start = self
+            .last_key_index
+            .saturating_sub(prize_table.runner_up_count)
+            .max(1);
+        let overlap_start = key_account.key_index.max(window_start);
+        let overlap_end = key_account.last_index()?.min(window_end);
+        if prize_table.runner_up_count > 0 && overlap_end >= overlap_start {
+            let runner_up_pool =
+                basis_points_of(self.prize_pool, prize_table.runner_up_basis_points)?;
+            let keys_in_window = overlap_end - overlap_start + 1;
+            let runner_up_prize = u64::try_from(
+                u128::from(runner_up_pool) * u128::from(keys_in_window)
+                    / u128::from(prize_table.runner_up_count),
+            )
+            .map_err(|_| CustomErrors::MathOverflow)?;
+            prize = prize
+                .checked_add(runner_up_prize)
+                .ok_or(CustomErrors::MathOverflow)?;
+        }
+        Ok(prize)
+    }
+
+    // current time in the round's timer mode
+    pub fn now(&self) -> Result<u64> {
+        self.config.timer_mode.now()
+    }
+
+    // caps a close time to the timer window and the hard end of the round
+    pub fn cap_close_slot(&self, close_slot: u64, now: u64) -> u64 {
+        let close_slot = close_slot.min(now.saturating_add(self.config.timer_window));
+        match self.config.round_hard_end_slot {
+            0 => close_slot,
+            hard_end_slot => close_slot.min(hard_end_slot),
+        }
+    }
+
+    // adds the timer increment for `count` keys to the remaining time
+    pub fn extend_timer(&mut self, count: u64, now: u64) {
+        let extended_slot = self
+            .config
+            .timer_increment
+            .checked_mul(count)
+            .and_then(|increment| self.round_close_slot.checked_add(increment))
+            .unwrap_or(u64::MAX);
+        self.round_close_slot = self.cap_close_slot(extended_slot, now);
+    }
+
+    // total price of the next `count` keys in base units, also used by clients to quote
+    pub fn quote_keys(&self, count: u64) -> Result<u64> {
+        let total = (1..=count).try_fold(0u64, |total, offset| {
+            let price = self.config.price_curve.price_for_index(
+                self.mint_counter.checked_add(offset)?,
+                self.config
+                    .token_decimals
+                    .checked_sub(self.config.price_decimals)?,
+            )?;
+            total.checked_add(price)
+        });
+        Ok(total.ok_or(CustomErrors::MathOverflow)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(key_index: u64, quantity: u64) -> NftKey {
+        NftKey {
+            key_index,
+            quantity,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn batch_winner_does_not_share_the_runner_up_pool() {
+        let mut round = Round {
+            prize_pool: 1_000_000,
+            // the last purchase bought keys 96 to 100
+            mint_counter: 100,
+            last_key_index: 96,
+            ..Default::default()
+        };
+        round.config.prize_table = PrizeTable {
+            winner_basis_points: 5000,
+            runner_up_count: 9,
+            runner_up_basis_points: 3000,
+            rollover_basis_points: 2000,
+        };
+
+        assert_eq!(round.prize_for_key(&key(96, 5)).unwrap(), 500_000);
+        // keys 87 to 95 are the 9 keys bought before the winner
+        assert_eq!(round.prize_for_key(&key(87, 9)).unwrap(), 300_000);
+        assert_eq!(round.prize_for_key(&key(91, 1)).unwrap(), 300_000 / 9);
+        assert_eq!(round.prize_for_key(&key(86, 1)).unwrap(), 0);
+    }
+
+    #[test]
+    fn round_without_keys_settles_with_the_team_claim() {
+        let mut round = Round {
+            status: RoundStatus::Active,
+            team_claimed: true,
+            ..Default::default()
+        };
+        round.update_status();
+        assert!(round.status == RoundStatus::Settled);
+
+        let mut round = Round {
+            status: RoundStatus::Active,
+            mint_counter: 1,
+            team_claimed: true,
+            ..Default::default()
+        };
+        round.update_status();
+        assert!(round.status == RoundStatus::Ended);
     }
 }
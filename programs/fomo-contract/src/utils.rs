@@ -1,14 +1,178 @@
-use anchor_lang::{prelude::Pubkey, Key};
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{
+        transfer_fee::TransferFeeConfig, BaseStateWithExtensions, ExtensionType,
+        StateWithExtensions,
+    },
+};
+use mpl_core::accounts::BaseAssetV1;
+use mpl_core::fetch_plugin;
 use mpl_core::types::{
-    Creator, FreezeDelegate, Plugin, PluginAuthority, PluginAuthorityPair, Royalties,
-    VerifiedCreators, VerifiedCreatorsSignature,
+    Attribute, Attributes, Creator, FreezeDelegate, Plugin, PluginAuthority, PluginAuthorityPair,
+    PluginType, Royalties, UpdateAuthority, VerifiedCreators, VerifiedCreatorsSignature,
+};
+
+use crate::{
+    errors::CustomErrors,
+    state::{KeyMetadata, NftKey, Round},
 };
 
-pub const TEAM_FEE_BASIS_POINTS: u64 = 420;
-pub const POOL_FEE_BASIS_POINTS: u64 = 690;
-pub const BURN_FEE_BASIS_POINTS: u64 = 990;
-pub const TREASURE_FEE_BASIS_POINTS: u64 = 7900;
-pub const SLOT_TO_CHANGE: u64 = 200000; // 200000
+pub const BASIS_POINTS_DENOMINATOR: u64 = 10000;
+// scale of Round::pool_per_key_acc
+pub const POOL_ACC_PRECISION: u128 = 1_000_000_000_000;
+// keys per create_keys call, keeps price summation and the mint within compute limits
+pub const MAX_KEYS_PER_PURCHASE: u64 = 50;
+// decimals of SOL, vault balances and payments of SOL rounds are in lamports
+pub const SOL_DECIMALS: u8 = 9;
+
+// share of `amount` for the given basis points, rounded down
+pub fn basis_points_of(amount: u64, basis_points: u16) -> Result<u64> {
+    let share = u128::from(amount)
+        .checked_mul(u128::from(basis_points))
+        .ok_or(CustomErrors::MathOverflow)?
+        / u128::from(BASIS_POINTS_DENOMINATOR);
+    Ok(u64::try_from(share).map_err(|_| CustomErrors::MathOverflow)?)
+}
+
+// Token-2022 mint extensions a round can run with, anything else could freeze,
+// hook or claw back the vaults
+const SUPPORTED_MINT_EXTENSIONS: [ExtensionType; 3] = [
+    ExtensionType::TransferFeeConfig,
+    ExtensionType::MetadataPointer,
+    ExtensionType::TokenMetadata,
+];
+
+// rejects Token-2022 mints with extensions the program does not handle
+pub fn check_mint_extensions(mint: &AccountInfo) -> Result<()> {
+    if *mint.owner != spl_token_2022::ID {
+        return Ok(());
+    }
+
+    let mint_data = mint.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+    for extension_type in mint_state.get_extension_types()? {
+        require!(
+            SUPPORTED_MINT_EXTENSIONS.contains(&extension_type),
+            CustomErrors::UnsupportedMintExtension
+        );
+    }
+    Ok(())
+}
+
+// transfer fee withheld when moving `amount` of the mint, 0 for mints without fees
+pub fn transfer_fee(mint: &AccountInfo, amount: u64) -> Result<u64> {
+    if *mint.owner != spl_token_2022::ID {
+        return Ok(0);
+    }
+
+    let mint_data = mint.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+    let Ok(transfer_fee_config) = mint_state.get_extension::<TransferFeeConfig>() else {
+        return Ok(0);
+    };
+    let fee = transfer_fee_config
+        .calculate_epoch_fee(Clock::get()?.epoch, amount)
+        .ok_or(CustomErrors::InvalidTransferFee)?;
+    Ok(fee)
+}
+
+// amount to send so that `received` reaches the destination after the transfer fee
+pub fn amount_with_transfer_fee(mint: &AccountInfo, received: u64) -> Result<u64> {
+    if *mint.owner != spl_token_2022::ID || received == 0 {
+        return Ok(received);
+    }
+
+    let mint_data = mint.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+    let Ok(transfer_fee_config) = mint_state.get_extension::<TransferFeeConfig>() else {
+        return Ok(received);
+    };
+    let fee = transfer_fee_config
+        .calculate_inverse_epoch_fee(Clock::get()?.epoch, received)
+        .ok_or(CustomErrors::InvalidTransferFee)?;
+    Ok(received
+        .checked_add(fee)
+        .ok_or(CustomErrors::InvalidTransferFee)?)
+}
+
+// reads a key asset and checks that the round minted it, and that
+// `expected_owner` holds it when given
+pub fn verify_key_asset(
+    asset: &AccountInfo,
+    round_account: &Account<Round>,
+    expected_owner: Option<Pubkey>,
+) -> Result<BaseAssetV1> {
+    let asset_data = BaseAssetV1::from_bytes(&asset.data.borrow())?;
+
+    if asset_data.update_authority != UpdateAuthority::Collection(round_account.collection) {
+        return Err(CustomErrors::InvalidAsset.into());
+    }
+
+    // the round signs every key it mints as a verified creator
+    let (_, verified_creators, _) =
+        fetch_plugin::<BaseAssetV1, VerifiedCreators>(asset, PluginType::VerifiedCreators)
+            .map_err(|_| CustomErrors::InvalidAsset)?;
+    require!(
+        verified_creators
+            .signatures
+            .iter()
+            .any(|sig| sig.verified && sig.address == round_account.key()),
+        CustomErrors::InvalidAsset
+    );
+
+    if let Some(expected_owner) = expected_owner {
+        require_keys_eq!(asset_data.owner, expected_owner, CustomErrors::Unauthorized);
+    }
+    Ok(asset_data)
+}
+
+// attributes of a key asset, rebuilt from its key account on every update
+pub fn key_attributes(round_seed: u64, key_account: &NftKey, is_winner: bool) -> Attributes {
+    let mut attribute_list = Vec::from([
+        Attribute {
+            key: String::from("round"),
+            value: round_seed.to_string(),
+        },
+        Attribute {
+            key: String::from("key_index"),
+            value: key_account.key_index.to_string(),
+        },
+        Attribute {
+            key: String::from("quantity"),
+            value: key_account.quantity.to_string(),
+        },
+        Attribute {
+            key: String::from("price_paid"),
+            value: key_account.price_paid.to_string(),
+        },
+        Attribute {
+            key: String::from("mint_slot"),
+            value: key_account.mint_slot.to_string(),
+        },
+    ]);
+
+    if is_winner {
+        attribute_list.push(Attribute {
+            key: String::from("winner"),
+            value: String::from("true"),
+        });
+    }
+    if key_account.prize_claimed {
+        attribute_list.push(Attribute {
+            key: String::from("prize_claimed"),
+            value: String::from("true"),
+        });
+    }
+    // burned keys have no asset left, an exited key with an asset was refunded
+    if key_account.exited == 1 {
+        attribute_list.push(Attribute {
+            key: String::from("refunded"),
+            value: String::from("true"),
+        });
+    }
+    Attributes { attribute_list }
+}
 
 pub struct Config {
     pub name: String,
@@ -34,10 +198,15 @@ impl Config {
             }]),
         }
     }
-    pub fn get_master(round_account: Pubkey) -> Config {
+    pub fn get_master(
+        round_account: Pubkey,
+        round_seed: u64,
+        key_metadata: &KeyMetadata,
+        key_account: &NftKey,
+    ) -> Config {
         Config {
-            name: String::from("Master Key"),
-            uri: String::from("http://"),
+            name: key_metadata.asset_name(key_account.key_index),
+            uri: key_metadata.asset_uri(key_account.key_index),
             plugins: Vec::from([
                 PluginAuthorityPair {
                     plugin: Plugin::FreezeDelegate(FreezeDelegate { frozen: true }),
@@ -52,28 +221,87 @@ impl Config {
                     }),
                     authority: Some(PluginAuthority::UpdateAuthority),
                 },
-            ]),
-        }
-    }
-    pub fn get_default(round_account: Pubkey) -> Config {
-        Config {
-            name: String::from("Key"),
-            uri: String::from("http://"),
-            plugins: Vec::from([
                 PluginAuthorityPair {
-                    plugin: Plugin::FreezeDelegate(FreezeDelegate { frozen: false }),
-                    authority: Some(PluginAuthority::UpdateAuthority),
-                },
-                PluginAuthorityPair {
-                    plugin: Plugin::VerifiedCreators(VerifiedCreators {
-                        signatures: Vec::from([VerifiedCreatorsSignature {
-                            verified: true,
-                            address: round_account,
-                        }]),
-                    }),
+                    plugin: Plugin::Attributes(key_attributes(round_seed, key_account, false)),
                     authority: Some(PluginAuthority::UpdateAuthority),
                 },
             ]),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mpl_core::types::Key;
+
+    fn asset_data(update_authority: UpdateAuthority) -> Vec<u8> {
+        BaseAssetV1 {
+            key: Key::AssetV1,
+            owner: Pubkey::new_unique(),
+            update_authority,
+            name: String::from("key"),
+            uri: String::new(),
+            seq: None,
+        }
+        .try_to_vec()
+        .unwrap()
+    }
+
+    // runs verify_key_asset against a round of `collection`
+    fn verify(collection: Pubkey, mut asset_data: Vec<u8>) -> Result<BaseAssetV1> {
+        let mut round_data = Vec::new();
+        Round {
+            collection,
+            ..Default::default()
+        }
+        .try_serialize(&mut round_data)
+        .unwrap();
+
+        let (round_key, asset_key) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (mut round_lamports, mut asset_lamports) = (0, 0);
+        let round_info = AccountInfo::new(
+            &round_key,
+            false,
+            false,
+            &mut round_lamports,
+            &mut round_data,
+            &crate::ID,
+            false,
+            0,
+        );
+        let asset_info = AccountInfo::new(
+            &asset_key,
+            false,
+            false,
+            &mut asset_lamports,
+            &mut asset_data,
+            &mpl_core::ID,
+            false,
+            0,
+        );
+        let round_account = Account::<Round>::try_from(&round_info).unwrap();
+        verify_key_asset(&asset_info, &round_account, None)
+    }
+
+    #[test]
+    fn rejects_an_asset_of_another_collection() {
+        let collection = Pubkey::new_unique();
+        let foreign = asset_data(UpdateAuthority::Collection(Pubkey::new_unique()));
+        assert_eq!(
+            verify(collection, foreign).unwrap_err(),
+            CustomErrors::InvalidAsset.into()
+        );
+    }
+
+    #[test]
+    fn rejects_an_asset_the_round_did_not_sign() {
+        // right collection, but no verified creators plugin
+        let collection = Pubkey::new_unique();
+        let unsigned = asset_data(UpdateAuthority::Collection(collection));
+        assert_eq!(
+            verify(collection, unsigned).unwrap_err(),
+            CustomErrors::InvalidAsset.into()
+        );
+    }
+}
@@ -0,0 +1,285 @@
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::{
+    token_2022::spl_token_2022::{
+        self,
+        extension::{BaseStateWithExtensions, ExtensionType, StateWithExtensions},
+    },
+    token_interface::{self, Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{amount_with_transfer_fee, errors::CustomErrors, state::Currency, transfer_fee};
+
+// accounts moving the currency of a round, the token ones are omitted for SOL rounds
+pub struct VaultAccounts<'a, 'info> {
+    pub currency: Currency,
+    pub token_mint: Option<&'a InterfaceAccount<'info, Mint>>,
+    pub token_program: Option<&'a Interface<'info, TokenInterface>>,
+}
+
+impl<'info> VaultAccounts<'_, 'info> {
+    fn token_accounts(&self) -> Result<(&InterfaceAccount<'info, Mint>, AccountInfo<'info>)> {
+        match (self.token_mint, self.token_program) {
+            (Some(token_mint), Some(token_program)) => {
+                Ok((token_mint, token_program.to_account_info()))
+            }
+            _ => err!(CustomErrors::TokenAccountsRequired),
+        }
+    }
+
+    // what can be paid out of a vault, SOL vaults keep their rent exemption
+    pub fn balance(&self, vault: &AccountInfo) -> Result<u64> {
+        match self.currency {
+            Currency::Token => {
+                let token_account = TokenAccount::try_deserialize(&mut &vault.data.borrow()[..])?;
+                Ok(token_account.amount)
+            }
+            Currency::Sol => Ok(vault
+                .lamports()
+                .saturating_sub(Rent::get()?.minimum_balance(0))),
+        }
+    }
+
+    // transfer fee withheld when moving `amount`, always 0 for SOL rounds
+    pub fn transfer_fee(&self, amount: u64) -> Result<u64> {
+        match self.currency {
+            Currency::Token => {
+                let (token_mint, _) = self.token_accounts()?;
+                transfer_fee(&token_mint.to_account_info(), amount)
+            }
+            Currency::Sol => Ok(0),
+        }
+    }
+
+    // amount to send so that `received` reaches the destination
+    pub fn amount_with_transfer_fee(&self, received: u64) -> Result<u64> {
+        match self.currency {
+            Currency::Token => {
+                let (token_mint, _) = self.token_accounts()?;
+                amount_with_transfer_fee(&token_mint.to_account_info(), received)
+            }
+            Currency::Sol => Ok(received),
+        }
+    }
+
+    // moves `amount` from a signer, or its token account, into a vault
+    pub fn deposit(
+        &self,
+        authority: &AccountInfo<'info>,
+        authority_ata: Option<&AccountInfo<'info>>,
+        vault: &AccountInfo<'info>,
+        system_program: &AccountInfo<'info>,
+        amount: u64,
+    ) -> Result<()> {
+        match self.currency {
+            Currency::Token => {
+                let (token_mint, token_program) = self.token_accounts()?;
+                let authority_ata = authority_ata.ok_or(CustomErrors::TokenAccountsRequired)?;
+
+                let transfer_instruction = token_interface::TransferChecked {
+                    from: authority_ata.clone(),
+                    mint: token_mint.to_account_info(),
+                    to: vault.clone(),
+                    authority: authority.clone(),
+                };
+
+                let cpi_ctx = CpiContext::new(token_program, transfer_instruction);
+
+                token_interface::transfer_checked(cpi_ctx, amount, token_mint.decimals)
+            }
+            Currency::Sol => {
+                let transfer_instruction = system_program::Transfer {
+                    from: authority.clone(),
+                    to: vault.clone(),
+                };
+
+                let cpi_ctx = CpiContext::new(system_program.clone(), transfer_instruction);
+
+                system_program::transfer(cpi_ctx, amount)
+            }
+        }
+    }
+
+    // moves `amount` out of a round vault to a wallet, or to its token account
+    pub fn withdraw(
+        &self,
+        round_account: &AccountInfo<'info>,
+        signer_seeds: &[&[&[u8]]],
+        vault: &AccountInfo<'info>,
+        recipient: &AccountInfo<'info>,
+        recipient_ata: Option<&AccountInfo<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        match self.currency {
+            Currency::Token => {
+                let (token_mint, token_program) = self.token_accounts()?;
+                let recipient_ata = recipient_ata.ok_or(CustomErrors::TokenAccountsRequired)?;
+
+                let transfer_instruction = token_interface::TransferChecked {
+                    from: vault.clone(),
+                    mint: token_mint.to_account_info(),
+                    to: recipient_ata.clone(),
+                    authority: round_account.clone(),
+                };
+
+                let cpi_ctx =
+                    CpiContext::new(token_program, transfer_instruction).with_signer(signer_seeds);
+
+                token_interface::transfer_checked(cpi_ctx, amount, token_mint.decimals)
+            }
+            Currency::Sol => {
+                // the program owns SOL vaults and moves their lamports directly
+                vault.sub_lamports(amount)?;
+                recipient.add_lamports(amount)?;
+                Ok(())
+            }
+        }
+    }
+
+    // burns `amount` from a signer, SOL is sent to the incinerator
+    pub fn burn(
+        &self,
+        authority: &AccountInfo<'info>,
+        authority_ata: Option<&AccountInfo<'info>>,
+        incinerator: Option<&AccountInfo<'info>>,
+        system_program: &AccountInfo<'info>,
+        amount: u64,
+    ) -> Result<()> {
+        match self.currency {
+            Currency::Token => {
+                let (token_mint, token_program) = self.token_accounts()?;
+                let authority_ata = authority_ata.ok_or(CustomErrors::TokenAccountsRequired)?;
+
+                let burn_instruction = token_interface::Burn {
+                    mint: token_mint.to_account_info(),
+                    from: authority_ata.clone(),
+                    authority: authority.clone(),
+                };
+
+                let cpi_ctx = CpiContext::new(token_program, burn_instruction);
+
+                token_interface::burn(cpi_ctx, amount)
+            }
+            Currency::Sol => {
+                let incinerator = incinerator.ok_or(CustomErrors::IncineratorRequired)?;
+
+                let transfer_instruction = system_program::Transfer {
+                    from: authority.clone(),
+                    to: incinerator.clone(),
+                };
+
+                let cpi_ctx = CpiContext::new(system_program.clone(), transfer_instruction);
+
+                system_program::transfer(cpi_ctx, amount)
+            }
+        }
+    }
+
+    // creates a vault PDA: a token account owned by the round, or an empty
+    // program owned account for SOL rounds
+    pub fn create_vault(
+        &self,
+        vault: &AccountInfo<'info>,
+        vault_seeds: &[&[u8]],
+        round_account: &AccountInfo<'info>,
+        payer: &AccountInfo<'info>,
+        system_program: &AccountInfo<'info>,
+    ) -> Result<()> {
+        match self.currency {
+            Currency::Token => {
+                let (token_mint, token_program) = self.token_accounts()?;
+                let space = token_account_space(&token_mint.to_account_info())?;
+
+                create_pda(
+                    vault,
+                    vault_seeds,
+                    space,
+                    token_program.key,
+                    payer,
+                    system_program,
+                )?;
+
+                let initialize_instruction = token_interface::InitializeAccount3 {
+                    account: vault.clone(),
+                    mint: token_mint.to_account_info(),
+                    authority: round_account.clone(),
+                };
+
+                let cpi_ctx = CpiContext::new(token_program, initialize_instruction);
+
+                token_interface::initialize_account3(cpi_ctx)
+            }
+            Currency::Sol => create_pda(vault, vault_seeds, 0, &crate::ID, payer, system_program),
+        }
+    }
+}
+
+// size of a token account of the mint, with the extensions the mint requires
+fn token_account_space(token_mint: &AccountInfo) -> Result<usize> {
+    if *token_mint.owner != spl_token_2022::ID {
+        return Ok(anchor_spl::token::TokenAccount::LEN);
+    }
+
+    let mint_data = token_mint.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+    let required_extensions =
+        ExtensionType::get_required_init_account_extensions(&mint_state.get_extension_types()?);
+    Ok(ExtensionType::try_calculate_account_len::<
+        spl_token_2022::state::Account,
+    >(&required_extensions)?)
+}
+
+// creates a rent exempt PDA, even if someone already sent lamports to its address
+fn create_pda<'info>(
+    pda: &AccountInfo<'info>,
+    seeds: &[&[u8]],
+    space: usize,
+    owner: &Pubkey,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    let signer_seeds: &[&[&[u8]]] = &[seeds];
+    let rent_exempt_lamports = Rent::get()?.minimum_balance(space);
+
+    if pda.lamports() == 0 {
+        let cpi_ctx = CpiContext::new(
+            system_program.clone(),
+            system_program::CreateAccount {
+                from: payer.clone(),
+                to: pda.clone(),
+            },
+        )
+        .with_signer(signer_seeds);
+
+        return system_program::create_account(cpi_ctx, rent_exempt_lamports, space as u64, owner);
+    }
+
+    let top_up = rent_exempt_lamports.saturating_sub(pda.lamports());
+    if top_up > 0 {
+        let cpi_ctx = CpiContext::new(
+            system_program.clone(),
+            system_program::Transfer {
+                from: payer.clone(),
+                to: pda.clone(),
+            },
+        );
+        system_program::transfer(cpi_ctx, top_up)?;
+    }
+
+    let cpi_ctx = CpiContext::new(
+        system_program.clone(),
+        system_program::Allocate {
+            account_to_allocate: pda.clone(),
+        },
+    )
+    .with_signer(signer_seeds);
+    system_program::allocate(cpi_ctx, space as u64)?;
+
+    let cpi_ctx = CpiContext::new(
+        system_program.clone(),
+        system_program::Assign {
+            account_to_assign: pda.clone(),
+        },
+    )
+    .with_signer(signer_seeds);
+    system_program::assign(cpi_ctx, owner)
+}
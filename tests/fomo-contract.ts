@@ -2,9 +2,30 @@ import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { FomoContract } from "../target/types/fomo_contract";
 import IDL from "../target/idl/fomo_contract.json";
-import { getPDA } from "./utils";
+import {
+  createAta,
+  createTransferFeeMint,
+  getAta,
+  getPDA,
+  mintToProvider,
+  TOKEN_2022_PROGRAM_ID,
+  tokenBalance,
+  waitForSlot,
+} from "./utils";
 import { BN } from "bn.js";
 import { TOKEN_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/utils/token";
+import { expect } from "chai";
+
+const MPL_CORE_PROGRAM_ID = new anchor.web3.PublicKey(
+  "CoREENxT6tW1HoK8ypY1SxRMZTcVPm7R94rH4PZNhX7d"
+);
+// allow-list entry for SOL rounds
+const NATIVE_MINT = new anchor.web3.PublicKey(
+  "So11111111111111111111111111111111111111112"
+);
+const INCINERATOR = new anchor.web3.PublicKey(
+  "1nc1nerator11111111111111111111111111111111"
+);
 
 describe("fomo-contract", () => {
   // Configure the client to use the local cluster.
@@ -19,7 +40,73 @@ describe("fomo-contract", () => {
   );
 
   const seed = Math.floor(Math.random() * 1000);
+  const collection = anchor.web3.Keypair.generate();
+
+  // 10 tokens for key #1, +10 tokens per key, 6 decimal token
+  const roundConfig = {
+    teamFeeBasisPoints: 420,
+    referralFeeBasisPoints: 100,
+    poolFeeBasisPoints: 690,
+    burnFeeBasisPoints: 990,
+    treasureFeeBasisPoints: 7900,
+    timerMode: { slot: {} },
+    timerWindow: new BN(200000),
+    timerIncrement: new BN(75),
+    roundHardEndSlot: new BN(0),
+    priceCurve: {
+      linear: { basePrice: new BN(10), priceStep: new BN(10) },
+    },
+    tokenDecimals: 6,
+    // curve prices are in whole tokens
+    priceDecimals: 0,
+    currency: { token: {} },
+    cancelKeyThreshold: new BN(10),
+    // prizes go to the wallet that bought the key, even if the asset moved
+    winnerPolicy: { originalBuyer: {} },
+    // last buyer 50%, previous 9 keys share 30%, 20% rolls over
+    prizeTable: {
+      winnerBasisPoints: 5000,
+      runnerUpCount: new BN(9),
+      runnerUpBasisPoints: 3000,
+      rolloverBasisPoints: 2000,
+    },
+  };
+
+  // key assets are named "Key #1", "Key #2", ...
+  const keyMetadata = {
+    name: "Key",
+    baseUri: "https://example.com/keys",
+  };
+
+  it("Initialize Config", async () => {
+    const [programConfig] = getPDA([Buffer.from("config")], programId);
+    const existing = await program.account.programConfig.fetchNullable(
+      programConfig
+    );
+    if (existing) {
+      return;
+    }
 
+    const [programData] = getPDA(
+      [programId.toBuffer()],
+      new anchor.web3.PublicKey("BPFLoaderUpgradeab1e11111111111111111111111")
+    );
+    const tx = await program.methods
+      .initializeConfig({
+        allowedMints: [tokenMint, NATIVE_MINT],
+        feeRecipient: program.provider.publicKey,
+        paused: false,
+      })
+      .accountsPartial({
+        authority: program.provider.publicKey,
+        programConfig,
+        program: programId,
+        programData,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+    console.log("Your transaction signature", tx);
+  });
   it("Create Round", async () => {
     const [roundAccount] = getPDA(
       [Buffer.from("round"), new BN(seed).toArrayLike(Buffer, "le", 8)],
@@ -27,18 +114,16 @@ describe("fomo-contract", () => {
     );
 
     const tx = await program.methods
-      .createRound(new BN(seed), {
-        name: "test",
-        uri: "",
-        plugins: [],
-        externalPluginAdapters: [],
-      })
+      .createRound(new BN(seed), "test", "", keyMetadata, roundConfig)
       .accounts({
         authority: program.provider.publicKey,
+        collection: collection.publicKey,
+        tokenMint,
         tokenProgram: TOKEN_PROGRAM_ID,
         systemProgram: anchor.web3.SystemProgram.programId,
         roundAccount: roundAccount,
       })
+      .signers([collection])
       .rpc();
     console.log("Your transaction signature", tx);
   });
@@ -61,7 +146,7 @@ describe("fomo-contract", () => {
       programId
     );
     const tx = await program.methods
-      .createRoundReserves()
+      .createReserves()
       .accounts({
         authority: program.provider.publicKey,
         tokenProgram: TOKEN_PROGRAM_ID,
@@ -75,14 +160,1287 @@ describe("fomo-contract", () => {
       .rpc();
     console.log("Your transaction signature", tx);
   });
-  it("Create Key", async () => {
+  it("Update Round Metadata before the first key", async () => {
+    const [roundAccount] = getPDA(
+      [Buffer.from("round"), new BN(seed).toArrayLike(Buffer, "le", 8)],
+      programId
+    );
+
+    await program.methods
+      .updateRoundMetadata({
+        collectionName: "test keys",
+        collectionUri: null,
+        keyMetadata: { name: "Test Key", baseUri: "https://example.com/test/" },
+      })
+      .accountsPartial({
+        authority: program.provider.publicKey,
+        roundAccount,
+        programConfig: getPDA([Buffer.from("config")], programId)[0],
+        collection: collection.publicKey,
+        logWrapper: null,
+        mplCore: MPL_CORE_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    const round = await program.account.round.fetch(roundAccount);
+    expect(round.keyMetadata.name).to.equal("Test Key");
+    expect(round.keyMetadata.baseUri).to.equal("https://example.com/test/");
+  });
+  const firstAsset = anchor.web3.Keypair.generate();
+  // key of another round, set by the SOL round test
+  let foreignAsset: anchor.web3.PublicKey;
+
+  const getKeyAccounts = () => {
     const [roundAccount] = getPDA(
       [Buffer.from("round"), new BN(seed).toArrayLike(Buffer, "le", 8)],
       programId
     );
+    const [poolVault] = getPDA(
+      [Buffer.from("pool"), roundAccount.toBuffer()],
+      programId
+    );
+    const [teamVault] = getPDA(
+      [Buffer.from("team"), roundAccount.toBuffer()],
+      programId
+    );
+    const [treasureVault] = getPDA(
+      [Buffer.from("vault"), roundAccount.toBuffer()],
+      programId
+    );
+    const authorityAta = anchor.utils.token.associatedAddress({
+      mint: tokenMint,
+      owner: program.provider.publicKey,
+    });
+    const [programConfig] = getPDA([Buffer.from("config")], programId);
+    return {
+      authority: program.provider.publicKey,
+      authorityAta,
+      roundAccount,
+      programConfig,
+      collection: collection.publicKey,
+      tokenMint,
+      teamVault,
+      poolVault,
+      treasureVault,
+      referrerAccount: null,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      incinerator: null,
+      logWrapper: null,
+      mplCore: MPL_CORE_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    };
+  };
+
+  it("Create First Key", async () => {
+    const accounts = getKeyAccounts();
+    const [keyAccount] = getPDA(
+      [
+        Buffer.from("key"),
+        accounts.roundAccount.toBuffer(),
+        new BN(1).toArrayLike(Buffer, "le", 8),
+      ],
+      programId
+    );
+
+    // no previous key exists on a fresh round
+    const tx = await program.methods
+      .createKey()
+      .accountsPartial({
+        ...accounts,
+        asset: firstAsset.publicKey,
+        currentAsset: null,
+        currentKeyAccount: null,
+        keyAccount,
+      })
+      .signers([firstAsset])
+      .rpc();
+    console.log("Your transaction signature", tx);
+
+    const round = await program.account.round.fetch(accounts.roundAccount);
+    expect(round.mintCounter.toNumber()).to.equal(1);
+    // 9.9% of the 10 token price is burned
+    expect(round.burnedAmount.toNumber()).to.equal(990_000);
+    const key = await program.account.nftKey.fetch(keyAccount);
+    expect(key.keyIndex.toNumber()).to.equal(1);
+    expect(key.nftMint.toBase58()).to.equal(firstAsset.publicKey.toBase58());
+    // the mint slot is also shown in the asset's Attributes plugin
+    expect(key.mintSlot.toNumber()).to.be.greaterThan(0);
+    expect(key.buyer.toBase58()).to.equal(
+      program.provider.publicKey.toBase58()
+    );
+  });
+  it("Create Key requires the current key after the first", async () => {
+    const accounts = getKeyAccounts();
+    const asset = anchor.web3.Keypair.generate();
+    const [keyAccount] = getPDA(
+      [
+        Buffer.from("key"),
+        accounts.roundAccount.toBuffer(),
+        new BN(2).toArrayLike(Buffer, "le", 8),
+      ],
+      programId
+    );
+
+    try {
+      await program.methods
+        .createKey()
+        .accountsPartial({
+          ...accounts,
+          asset: asset.publicKey,
+          currentAsset: null,
+          currentKeyAccount: null,
+          keyAccount,
+        })
+        .signers([asset])
+        .rpc();
+      expect.fail("second key was minted without the current key");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("CurrentKeyRequired");
+    }
+  });
+  it("Create Second Key", async () => {
+    const accounts = getKeyAccounts();
+    const asset = anchor.web3.Keypair.generate();
+    const [currentKeyAccount] = getPDA(
+      [
+        Buffer.from("key"),
+        accounts.roundAccount.toBuffer(),
+        new BN(1).toArrayLike(Buffer, "le", 8),
+      ],
+      programId
+    );
     const [keyAccount] = getPDA(
-      [Buffer.from("key"), new BN(seed).toArrayLike(Buffer, "le", 8)],
+      [
+        Buffer.from("key"),
+        accounts.roundAccount.toBuffer(),
+        new BN(2).toArrayLike(Buffer, "le", 8),
+      ],
       programId
     );
+
+    const tx = await program.methods
+      .createKey()
+      .accountsPartial({
+        ...accounts,
+        asset: asset.publicKey,
+        currentAsset: firstAsset.publicKey,
+        currentKeyAccount,
+        keyAccount,
+      })
+      .signers([asset])
+      .rpc();
+    console.log("Your transaction signature", tx);
+
+    const round = await program.account.round.fetch(accounts.roundAccount);
+    expect(round.mintCounter.toNumber()).to.equal(2);
+  });
+  it("Create Keys in a batch", async () => {
+    const accounts = getKeyAccounts();
+    const asset = anchor.web3.Keypair.generate();
+    const round = await program.account.round.fetch(accounts.roundAccount);
+    const [currentKeyAccount] = getPDA(
+      [
+        Buffer.from("key"),
+        accounts.roundAccount.toBuffer(),
+        round.lastKeyIndex.toArrayLike(Buffer, "le", 8),
+      ],
+      programId
+    );
+    const currentKey = await program.account.nftKey.fetch(currentKeyAccount);
+    const firstIndex = round.mintCounter.addn(1);
+    const [keyAccount] = getPDA(
+      [
+        Buffer.from("key"),
+        accounts.roundAccount.toBuffer(),
+        firstIndex.toArrayLike(Buffer, "le", 8),
+      ],
+      programId
+    );
+
+    const tx = await program.methods
+      .createKeys(new BN(3))
+      .accountsPartial({
+        ...accounts,
+        asset: asset.publicKey,
+        currentAsset: currentKey.nftMint,
+        currentKeyAccount,
+        keyAccount,
+      })
+      .signers([asset])
+      .rpc();
+    console.log("Your transaction signature", tx);
+
+    const updated = await program.account.round.fetch(accounts.roundAccount);
+    expect(updated.mintCounter.toNumber()).to.equal(
+      round.mintCounter.toNumber() + 3
+    );
+    expect(updated.lastKeyIndex.toNumber()).to.equal(firstIndex.toNumber());
+    const key = await program.account.nftKey.fetch(keyAccount);
+    expect(key.quantity.toNumber()).to.equal(3);
+    expect(updated.leaderKey.toBase58()).to.equal(asset.publicKey.toBase58());
+    expect(updated.lastBuyer.toBase58()).to.equal(
+      program.provider.publicKey.toBase58()
+    );
+  });
+  it("Leader key stays frozen while the round is live", async () => {
+    const accounts = getKeyAccounts();
+    const round = await program.account.round.fetch(accounts.roundAccount);
+    const [keyAccount] = getPDA(
+      [
+        Buffer.from("key"),
+        accounts.roundAccount.toBuffer(),
+        round.lastKeyIndex.toArrayLike(Buffer, "le", 8),
+      ],
+      programId
+    );
+
+    try {
+      await program.methods
+        .burnKey()
+        .accountsPartial({
+          authority: accounts.authority,
+          authorityAta: accounts.authorityAta,
+          roundAccount: accounts.roundAccount,
+          programConfig: accounts.programConfig,
+          collection: accounts.collection,
+          asset: round.leaderKey,
+          keyAccount,
+          tokenMint,
+          poolVault: accounts.poolVault,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          logWrapper: null,
+          mplCore: MPL_CORE_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+      expect.fail("the leader key was burned");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("LeaderKeyLocked");
+    }
+
+    // only thawed once the winner has claimed
+    try {
+      await program.methods
+        .thawLeader()
+        .accountsPartial({
+          authority: accounts.authority,
+          roundAccount: accounts.roundAccount,
+          programConfig: accounts.programConfig,
+          asset: round.leaderKey,
+          collection: accounts.collection,
+          logWrapper: null,
+          mplCore: MPL_CORE_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+      expect.fail("the leader was thawed during the round");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("LeaderKeyLocked");
+    }
+  });
+  it("Update Round Metadata fails once keys were sold", async () => {
+    const accounts = getKeyAccounts();
+    try {
+      await program.methods
+        .updateRoundMetadata({
+          collectionName: null,
+          collectionUri: null,
+          keyMetadata: keyMetadata,
+        })
+        .accountsPartial({
+          authority: accounts.authority,
+          roundAccount: accounts.roundAccount,
+          programConfig: accounts.programConfig,
+          collection: accounts.collection,
+          logWrapper: null,
+          mplCore: MPL_CORE_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+      expect.fail("metadata was updated after the round started");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("RoundStarted");
+    }
+  });
+  it("Burn Key rejects a wallet that does not hold the key", async () => {
+    const accounts = getKeyAccounts();
+    const stranger = anchor.web3.Keypair.generate();
+    const [keyAccount] = getPDA(
+      [
+        Buffer.from("key"),
+        accounts.roundAccount.toBuffer(),
+        new BN(1).toArrayLike(Buffer, "le", 8),
+      ],
+      programId
+    );
+
+    try {
+      await program.methods
+        .burnKey()
+        .accountsPartial({
+          authority: stranger.publicKey,
+          authorityAta: null,
+          roundAccount: accounts.roundAccount,
+          programConfig: accounts.programConfig,
+          collection: accounts.collection,
+          asset: firstAsset.publicKey,
+          keyAccount,
+          tokenMint,
+          poolVault: accounts.poolVault,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          logWrapper: null,
+          mplCore: MPL_CORE_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([stranger])
+        .rpc();
+      expect.fail("a key was burned by a wallet that does not hold it");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("Unauthorized");
+    }
+  });
+  it("Claim Dividends keeps the key", async () => {
+    const accounts = getKeyAccounts();
+    const [keyAccount] = getPDA(
+      [
+        Buffer.from("key"),
+        accounts.roundAccount.toBuffer(),
+        new BN(1).toArrayLike(Buffer, "le", 8),
+      ],
+      programId
+    );
+
+    const tx = await program.methods
+      .claimDividends()
+      .accountsPartial({
+        authority: accounts.authority,
+        authorityAta: accounts.authorityAta,
+        roundAccount: accounts.roundAccount,
+        programConfig: accounts.programConfig,
+        asset: firstAsset.publicKey,
+        keyAccount,
+        tokenMint,
+        poolVault: accounts.poolVault,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+    console.log("Your transaction signature", tx);
+
+    // key #1 earned from every purchase after it
+    const key = await program.account.nftKey.fetch(keyAccount);
+    expect(key.exited).to.equal(0);
+    expect(key.dividendsClaimed.toNumber()).to.be.greaterThan(0);
+  });
+  it("Pause Round blocks purchases", async () => {
+    const accounts = getKeyAccounts();
+    await program.methods
+      .pauseRound()
+      .accountsPartial({
+        authority: accounts.authority,
+        roundAccount: accounts.roundAccount,
+      })
+      .rpc();
+
+    const round = await program.account.round.fetch(accounts.roundAccount);
+    expect(round.paused).to.equal(true);
+    const [currentKeyAccount] = getPDA(
+      [
+        Buffer.from("key"),
+        accounts.roundAccount.toBuffer(),
+        round.lastKeyIndex.toArrayLike(Buffer, "le", 8),
+      ],
+      programId
+    );
+    const currentKey = await program.account.nftKey.fetch(currentKeyAccount);
+    const [keyAccount] = getPDA(
+      [
+        Buffer.from("key"),
+        accounts.roundAccount.toBuffer(),
+        round.mintCounter.addn(1).toArrayLike(Buffer, "le", 8),
+      ],
+      programId
+    );
+    const asset = anchor.web3.Keypair.generate();
+    try {
+      await program.methods
+        .createKey()
+        .accountsPartial({
+          ...accounts,
+          asset: asset.publicKey,
+          currentAsset: currentKey.nftMint,
+          currentKeyAccount,
+          keyAccount,
+        })
+        .signers([asset])
+        .rpc();
+      expect.fail("key was minted while the round was paused");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("RoundPaused");
+    }
+
+    await program.methods
+      .unpauseRound()
+      .accountsPartial({
+        authority: accounts.authority,
+        roundAccount: accounts.roundAccount,
+      })
+      .rpc();
+
+    // the paused time is given back to the round
+    const unpaused = await program.account.round.fetch(accounts.roundAccount);
+    expect(unpaused.paused).to.equal(false);
+    expect(unpaused.roundCloseSlot.gte(round.roundCloseSlot)).to.equal(true);
+  });
+  it("SOL Round takes lamports for keys", async () => {
+    const solSeed = seed + 1000;
+    const solCollection = anchor.web3.Keypair.generate();
+    const [roundAccount] = getPDA(
+      [Buffer.from("round"), new BN(solSeed).toArrayLike(Buffer, "le", 8)],
+      programId
+    );
+    const [poolVault] = getPDA(
+      [Buffer.from("pool"), roundAccount.toBuffer()],
+      programId
+    );
+    const [teamVault] = getPDA(
+      [Buffer.from("team"), roundAccount.toBuffer()],
+      programId
+    );
+    const [treasureVault] = getPDA(
+      [Buffer.from("vault"), roundAccount.toBuffer()],
+      programId
+    );
+    const [programConfig] = getPDA([Buffer.from("config")], programId);
+
+    // 0.1 SOL per key, priced in thousandths of a SOL
+    await program.methods
+      .createRound(new BN(solSeed), "sol", "", keyMetadata, {
+        ...roundConfig,
+        priceCurve: { constant: { price: new BN(100) } },
+        tokenDecimals: 9,
+        priceDecimals: 3,
+        currency: { sol: {} },
+      })
+      .accountsPartial({
+        authority: program.provider.publicKey,
+        collection: solCollection.publicKey,
+        roundAccount,
+        programConfig,
+        tokenMint: null,
+        teamVault,
+        tokenProgram: null,
+        logWrapper: null,
+        mplCore: MPL_CORE_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([solCollection])
+      .rpc();
+    await program.methods
+      .createReserves()
+      .accountsPartial({
+        authority: program.provider.publicKey,
+        roundAccount,
+        programConfig,
+        tokenMint: null,
+        poolVault,
+        treasureVault,
+        tokenProgram: null,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    const treasureBefore = await program.provider.connection.getBalance(
+      treasureVault
+    );
+    const asset = anchor.web3.Keypair.generate();
+    foreignAsset = asset.publicKey;
+    const [keyAccount] = getPDA(
+      [
+        Buffer.from("key"),
+        roundAccount.toBuffer(),
+        new BN(1).toArrayLike(Buffer, "le", 8),
+      ],
+      programId
+    );
+    await program.methods
+      .createKey()
+      .accountsPartial({
+        authority: program.provider.publicKey,
+        authorityAta: null,
+        asset: asset.publicKey,
+        currentAsset: null,
+        roundAccount,
+        programConfig,
+        collection: solCollection.publicKey,
+        keyAccount,
+        currentKeyAccount: null,
+        referrerAccount: null,
+        tokenMint: null,
+        teamVault,
+        poolVault,
+        treasureVault,
+        tokenProgram: null,
+        incinerator: INCINERATOR,
+        logWrapper: null,
+        mplCore: MPL_CORE_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([asset])
+      .rpc();
+
+    const round = await program.account.round.fetch(roundAccount);
+    expect(round.config.currency).to.deep.equal({ sol: {} });
+    expect(round.burnedAmount.toNumber()).to.equal(9_900_000);
+    // 79% of the price reaches the treasure vault
+    const treasureAfter = await program.provider.connection.getBalance(
+      treasureVault
+    );
+    expect(treasureAfter - treasureBefore).to.equal(79_000_000);
+  });
+  it("Burn Key rejects an asset that is not the key account's", async () => {
+    const accounts = getKeyAccounts();
+    const [keyAccount] = getPDA(
+      [
+        Buffer.from("key"),
+        accounts.roundAccount.toBuffer(),
+        new BN(1).toArrayLike(Buffer, "le", 8),
+      ],
+      programId
+    );
+
+    try {
+      await program.methods
+        .burnKey()
+        .accountsPartial({
+          authority: accounts.authority,
+          authorityAta: accounts.authorityAta,
+          roundAccount: accounts.roundAccount,
+          programConfig: accounts.programConfig,
+          collection: accounts.collection,
+          asset: foreignAsset,
+          keyAccount,
+          tokenMint,
+          poolVault: accounts.poolVault,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          logWrapper: null,
+          mplCore: MPL_CORE_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+      expect.fail("a key of another round was burned");
+    } catch (err) {
+      // rejected by the asset constraint before verify_key_asset runs
+      expect(err.error.errorCode.code).to.equal("ConstraintRaw");
+    }
+  });
+
+  // rounds below run to their end, their timers only last 100 slots
+  const connection = program.provider.connection;
+  const provider = program.provider as anchor.AnchorProvider;
+  const [programConfig] = getPDA([Buffer.from("config")], programId);
+  const shortRoundConfig = {
+    ...roundConfig,
+    timerWindow: new BN(100),
+    timerIncrement: new BN(1),
+    // 10 tokens per key
+    priceCurve: { constant: { price: new BN(10) } },
+  };
+  const keyPDA = (roundAccount: anchor.web3.PublicKey, keyIndex: BN) =>
+    getPDA(
+      [
+        Buffer.from("key"),
+        roundAccount.toBuffer(),
+        keyIndex.toArrayLike(Buffer, "le", 8),
+      ],
+      programId
+    )[0];
+
+  interface TestRound {
+    roundAccount: anchor.web3.PublicKey;
+    collection: anchor.web3.PublicKey;
+    poolVault: anchor.web3.PublicKey;
+    teamVault: anchor.web3.PublicKey;
+    treasureVault: anchor.web3.PublicKey;
+    tokenMint: anchor.web3.PublicKey;
+    tokenProgram: anchor.web3.PublicKey;
+    authorityAta: anchor.web3.PublicKey;
+  }
+  interface TestKey {
+    asset: anchor.web3.PublicKey;
+    keyAccount: anchor.web3.PublicKey;
+    // transaction that bought the key
+    signature: string;
+  }
+
+  const createTestRound = async (
+    roundSeed: number,
+    config: Parameters<typeof program.methods.createRound>[4],
+    mint: anchor.web3.PublicKey = tokenMint,
+    tokenProgram: anchor.web3.PublicKey = TOKEN_PROGRAM_ID
+  ): Promise<TestRound> => {
+    const roundCollection = anchor.web3.Keypair.generate();
+    const [roundAccount] = getPDA(
+      [Buffer.from("round"), new BN(roundSeed).toArrayLike(Buffer, "le", 8)],
+      programId
+    );
+    const [poolVault] = getPDA(
+      [Buffer.from("pool"), roundAccount.toBuffer()],
+      programId
+    );
+    const [teamVault] = getPDA(
+      [Buffer.from("team"), roundAccount.toBuffer()],
+      programId
+    );
+    const [treasureVault] = getPDA(
+      [Buffer.from("vault"), roundAccount.toBuffer()],
+      programId
+    );
+
+    await program.methods
+      .createRound(new BN(roundSeed), "short", "", keyMetadata, config)
+      .accountsPartial({
+        authority: provider.publicKey,
+        collection: roundCollection.publicKey,
+        roundAccount,
+        programConfig,
+        tokenMint: mint,
+        teamVault,
+        tokenProgram,
+        logWrapper: null,
+        mplCore: MPL_CORE_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([roundCollection])
+      .rpc();
+    await program.methods
+      .createReserves()
+      .accountsPartial({
+        authority: provider.publicKey,
+        roundAccount,
+        programConfig,
+        tokenMint: mint,
+        poolVault,
+        treasureVault,
+        tokenProgram,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    return {
+      roundAccount,
+      collection: roundCollection.publicKey,
+      poolVault,
+      teamVault,
+      treasureVault,
+      tokenMint: mint,
+      tokenProgram,
+      authorityAta: getAta(mint, provider.publicKey, tokenProgram),
+    };
+  };
+
+  // buys the next `quantity` keys of a round as the provider
+  const buyKey = async (
+    testRound: TestRound,
+    quantity = 1,
+    referrerAccount: anchor.web3.PublicKey | null = null
+  ): Promise<TestKey> => {
+    const round = await program.account.round.fetch(testRound.roundAccount);
+    const asset = anchor.web3.Keypair.generate();
+    const isFirst = round.mintCounter.isZero();
+    const keyAccount = keyPDA(
+      testRound.roundAccount,
+      round.mintCounter.addn(1)
+    );
+    const currentKeyAccount = isFirst
+      ? null
+      : keyPDA(testRound.roundAccount, round.lastKeyIndex);
+    const currentAsset = isFirst
+      ? null
+      : (await program.account.nftKey.fetch(currentKeyAccount)).nftMint;
+
+    const signature = await program.methods
+      .createKeys(new BN(quantity))
+      .accountsPartial({
+        authority: provider.publicKey,
+        authorityAta: testRound.authorityAta,
+        asset: asset.publicKey,
+        currentAsset,
+        roundAccount: testRound.roundAccount,
+        programConfig,
+        collection: testRound.collection,
+        keyAccount,
+        currentKeyAccount,
+        referrerAccount,
+        tokenMint: testRound.tokenMint,
+        teamVault: testRound.teamVault,
+        poolVault: testRound.poolVault,
+        treasureVault: testRound.treasureVault,
+        tokenProgram: testRound.tokenProgram,
+        incinerator: null,
+        logWrapper: null,
+        mplCore: MPL_CORE_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([asset])
+      .rpc({ commitment: "confirmed" });
+    return { asset: asset.publicKey, keyAccount, signature };
+  };
+
+  const waitForRoundEnd = async (roundAccount: anchor.web3.PublicKey) => {
+    const round = await program.account.round.fetch(roundAccount);
+    await waitForSlot(connection, round.roundCloseSlot.toNumber() + 1);
+  };
+
+  const claimRound = (testRound: TestRound) =>
+    program.methods.claimRound().accountsPartial({
+      authority: provider.publicKey,
+      authorityAta: testRound.authorityAta,
+      roundAccount: testRound.roundAccount,
+      programConfig,
+      tokenMint: testRound.tokenMint,
+      teamVault: testRound.teamVault,
+      tokenProgram: testRound.tokenProgram,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    });
+
+  const winnerClaim = (
+    testRound: TestRound,
+    key: TestKey,
+    authority: anchor.web3.PublicKey = provider.publicKey
+  ) =>
+    program.methods.winnerClaim().accountsPartial({
+      authority,
+      authorityAta: getAta(
+        testRound.tokenMint,
+        authority,
+        testRound.tokenProgram
+      ),
+      roundAccount: testRound.roundAccount,
+      programConfig,
+      asset: key.asset,
+      keyAccount: key.keyAccount,
+      tokenMint: testRound.tokenMint,
+      treasureVault: testRound.treasureVault,
+      tokenProgram: testRound.tokenProgram,
+      collection: testRound.collection,
+      logWrapper: null,
+      mplCore: MPL_CORE_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    });
+
+  it("Claim Round and Winner Claim settle the round", async () => {
+    const testRound = await createTestRound(seed + 2000, shortRoundConfig);
+    const key = await buyKey(testRound);
+
+    try {
+      await claimRound(testRound).rpc();
+      expect.fail("the team claimed before the round ended");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("RoundNotOver");
+    }
+
+    await waitForRoundEnd(testRound.roundAccount);
+    const teamBefore = await tokenBalance(connection, testRound.teamVault);
+    await claimRound(testRound).rpc();
+
+    // 4.2% of the 10 token key
+    expect(teamBefore).to.equal(420_000);
+    expect(await tokenBalance(connection, testRound.teamVault)).to.equal(0);
+    let round = await program.account.round.fetch(testRound.roundAccount);
+    expect(round.teamClaimed).to.equal(true);
+    expect(round.status).to.deep.equal({ ended: {} });
+
+    try {
+      await claimRound(testRound).rpc();
+      expect.fail("the team claimed twice");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("AlreadyClaimed");
+    }
+
+    await winnerClaim(testRound, key).rpc();
+
+    round = await program.account.round.fetch(testRound.roundAccount);
+    expect(round.winnerClaimed).to.equal(true);
+    expect(round.winner.toBase58()).to.equal(provider.publicKey.toBase58());
+    expect(round.status).to.deep.equal({ settled: {} });
+  });
+
+  it("Winner Claim settles a round with an empty treasure", async () => {
+    // the treasure share goes to the pool instead
+    const testRound = await createTestRound(seed + 2100, {
+      ...shortRoundConfig,
+      poolFeeBasisPoints: 690 + 7900,
+      treasureFeeBasisPoints: 0,
+    });
+    const key = await buyKey(testRound);
+    await waitForRoundEnd(testRound.roundAccount);
+    await claimRound(testRound).rpc();
+
+    await winnerClaim(testRound, key).rpc();
+
+    const round = await program.account.round.fetch(testRound.roundAccount);
+    expect(round.prizePool.toNumber()).to.equal(0);
+    expect(round.prizePoolSet).to.equal(true);
+    expect(round.winnerClaimed).to.equal(true);
+    expect(round.status).to.deep.equal({ settled: {} });
+  });
+  it("Burn Key pays dividends pro rata to the keys live at each purchase", async () => {
+    const testRound = await createTestRound(seed + 3000, shortRoundConfig);
+    const firstKey = await buyKey(testRound);
+    const batchKey = await buyKey(testRound, 3);
+    const lateKey = await buyKey(testRound);
+
+    // nothing was live to earn the pool fee of key #1
+    const round = await program.account.round.fetch(testRound.roundAccount);
+    expect(round.poolUnallocated.toNumber()).to.equal(690_000);
+
+    // dividends are scaled by POOL_ACC_PRECISION on chain
+    const precision = new BN(1_000_000_000_000);
+    const pendingDividends = async (key: TestKey) => {
+      const keyData = await program.account.nftKey.fetch(key.keyAccount);
+      return round.poolPerKeyAcc
+        .mul(keyData.quantity)
+        .sub(keyData.rewardDebt)
+        .div(precision)
+        .toNumber();
+    };
+    // the batch earned a quarter of the late key's fee each, the late key
+    // gets nothing from the fees paid before it
+    expect(await pendingDividends(batchKey)).to.equal(3 * 172_500);
+    expect(await pendingDividends(lateKey)).to.equal(0);
+
+    const balanceBefore = await tokenBalance(
+      connection,
+      testRound.authorityAta
+    );
+    await program.methods
+      .burnKey()
+      .accountsPartial({
+        authority: provider.publicKey,
+        authorityAta: testRound.authorityAta,
+        roundAccount: testRound.roundAccount,
+        programConfig,
+        collection: testRound.collection,
+        asset: firstKey.asset,
+        keyAccount: firstKey.keyAccount,
+        tokenMint: testRound.tokenMint,
+        poolVault: testRound.poolVault,
+        tokenProgram: testRound.tokenProgram,
+        logWrapper: null,
+        mplCore: MPL_CORE_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    // key #1 was alone for the batch and shared the late key's fee with it
+    expect(
+      (await tokenBalance(connection, testRound.authorityAta)) - balanceBefore
+    ).to.equal(3 * 690_000 + 172_500);
+  });
+
+  const referrer = anchor.web3.Keypair.generate();
+
+  it("Claim Referral pays the referrer of a purchase", async () => {
+    const testRound = await createTestRound(seed + 4000, shortRoundConfig);
+    const { roundAccount, teamVault } = testRound;
+
+    // the referrer pays for its referrer account
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        anchor.web3.SystemProgram.transfer({
+          fromPubkey: provider.publicKey,
+          toPubkey: referrer.publicKey,
+          lamports: 50_000_000,
+        })
+      )
+    );
+    const [referrerAccount] = getPDA(
+      [
+        Buffer.from("referrer"),
+        roundAccount.toBuffer(),
+        referrer.publicKey.toBuffer(),
+      ],
+      programId
+    );
+    await program.methods
+      .createReferrer()
+      .accountsPartial({
+        authority: referrer.publicKey,
+        roundAccount,
+        programConfig,
+        referrerAccount,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([referrer])
+      .rpc();
+
+    await buyKey(testRound, 1, referrerAccount);
+
+    // 1% of the 10 token key, held in the team vault until claimed
+    let round = await program.account.round.fetch(roundAccount);
+    expect(round.referralOwed.toNumber()).to.equal(100_000);
+
+    const referrerAta = await createAta(
+      provider,
+      testRound.tokenMint,
+      referrer.publicKey,
+      testRound.tokenProgram
+    );
+    const teamBefore = await tokenBalance(connection, teamVault);
+    await program.methods
+      .claimReferral()
+      .accountsPartial({
+        authority: referrer.publicKey,
+        authorityAta: referrerAta,
+        roundAccount,
+        programConfig,
+        referrerAccount,
+        tokenMint: testRound.tokenMint,
+        teamVault,
+        tokenProgram: testRound.tokenProgram,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([referrer])
+      .rpc();
+
+    expect(teamBefore - (await tokenBalance(connection, teamVault))).to.equal(
+      100_000
+    );
+    expect(await tokenBalance(connection, referrerAta)).to.equal(100_000);
+    round = await program.account.round.fetch(roundAccount);
+    expect(round.referralOwed.toNumber()).to.equal(0);
+  });
+
+  // round run to its end by the tests below
+  let prizeRound: TestRound;
+  let prizeKeys: TestKey[];
+
+  it("Winner Claim pays the winner and a runner up", async () => {
+    // the prize follows the asset
+    prizeRound = await createTestRound(seed + 5000, {
+      ...shortRoundConfig,
+      winnerPolicy: { currentHolder: {} },
+    });
+    prizeKeys = [
+      await buyKey(prizeRound),
+      await buyKey(prizeRound),
+      await buyKey(prizeRound),
+    ];
+    await waitForRoundEnd(prizeRound.roundAccount);
+
+    const { treasureVault } = prizeRound;
+    const treasureBefore = await tokenBalance(connection, treasureVault);
+    // 79% of three 10 token keys
+    expect(treasureBefore).to.equal(23_700_000);
+
+    await winnerClaim(prizeRound, prizeKeys[2]).rpc();
+
+    const round = await program.account.round.fetch(prizeRound.roundAccount);
+    expect(round.prizePool.toNumber()).to.equal(treasureBefore);
+    expect(round.winnerClaimed).to.equal(true);
+    expect(round.status).to.deep.equal({ ended: {} });
+    // 50% for the last key
+    expect(
+      treasureBefore - (await tokenBalance(connection, treasureVault))
+    ).to.equal(11_850_000);
+
+    // keys #1 and #2 are runner ups, each gets 1/9 of the 30% share
+    await winnerClaim(prizeRound, prizeKeys[0]).rpc();
+    expect(
+      treasureBefore - (await tokenBalance(connection, treasureVault))
+    ).to.equal(11_850_000 + 790_000);
+
+    try {
+      await winnerClaim(prizeRound, prizeKeys[2]).rpc();
+      expect.fail("the prize was claimed twice");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("AlreadyClaimed");
+    }
+
+    const key = await program.account.nftKey.fetch(prizeKeys[0].keyAccount);
+    expect(key.prizeClaimed).to.equal(true);
+  });
+  it("Rollover Round moves the rollover share to the next round", async () => {
+    await claimRound(prizeRound).rpc();
+    const nextRound = await createTestRound(seed + 6000, shortRoundConfig);
+
+    await program.methods
+      .rolloverRound()
+      .accountsPartial({
+        authority: provider.publicKey,
+        roundAccount: prizeRound.roundAccount,
+        programConfig,
+        nextRoundAccount: nextRound.roundAccount,
+        tokenMint: prizeRound.tokenMint,
+        treasureVault: prizeRound.treasureVault,
+        poolVault: prizeRound.poolVault,
+        nextTreasureVault: nextRound.treasureVault,
+        tokenProgram: prizeRound.tokenProgram,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    const round = await program.account.round.fetch(prizeRound.roundAccount);
+    expect(round.status).to.deep.equal({ settled: {} });
+    expect(round.nextRound.toBase58()).to.equal(
+      nextRound.roundAccount.toBase58()
+    );
+    const next = await program.account.round.fetch(nextRound.roundAccount);
+    expect(next.previousRound.toBase58()).to.equal(
+      prizeRound.roundAccount.toBase58()
+    );
+    // 20% of the prize pool and the pool fee paid before key #1 was live
+    expect(await tokenBalance(connection, nextRound.treasureVault)).to.equal(
+      4_740_000 + 690_000
+    );
+    expect(round.poolUnallocated.toNumber()).to.equal(0);
+  });
+
+  const cancelRound = (testRound: TestRound) =>
+    program.methods.cancelRound().accountsPartial({
+      authority: provider.publicKey,
+      authorityAta: testRound.authorityAta,
+      roundAccount: testRound.roundAccount,
+      programConfig,
+      tokenMint: testRound.tokenMint,
+      teamVault: testRound.teamVault,
+      poolVault: testRound.poolVault,
+      treasureVault: testRound.treasureVault,
+      tokenProgram: testRound.tokenProgram,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    });
+
+  const refundKey = (testRound: TestRound, key: TestKey) =>
+    program.methods.refundKey().accountsPartial({
+      authority: provider.publicKey,
+      authorityAta: testRound.authorityAta,
+      roundAccount: testRound.roundAccount,
+      programConfig,
+      asset: key.asset,
+      keyAccount: key.keyAccount,
+      tokenMint: testRound.tokenMint,
+      treasureVault: testRound.treasureVault,
+      tokenProgram: testRound.tokenProgram,
+      collection: testRound.collection,
+      logWrapper: null,
+      mplCore: MPL_CORE_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    });
+
+  it("Cancel Round tops up the treasure and Refund Key repays the key", async () => {
+    const testRound = await createTestRound(seed + 7000, shortRoundConfig);
+    const key = await buyKey(testRound);
+
+    await cancelRound(testRound).rpc();
+
+    // the burned 9.9% is made up by the authority
+    let round = await program.account.round.fetch(testRound.roundAccount);
+    expect(round.status).to.deep.equal({ cancelled: {} });
+    expect(round.refundableAmount.toNumber()).to.equal(10_000_000);
+    expect(await tokenBalance(connection, testRound.treasureVault)).to.equal(
+      10_000_000
+    );
+
+    const balanceBefore = await tokenBalance(
+      connection,
+      testRound.authorityAta
+    );
+    await refundKey(testRound, key).rpc();
+
+    expect(
+      (await tokenBalance(connection, testRound.authorityAta)) - balanceBefore
+    ).to.equal(10_000_000);
+    expect(await tokenBalance(connection, testRound.treasureVault)).to.equal(0);
+    round = await program.account.round.fetch(testRound.roundAccount);
+    expect(round.refundableAmount.toNumber()).to.equal(0);
+    const refunded = await program.account.nftKey.fetch(key.keyAccount);
+    expect(refunded.exited).to.equal(1);
+  });
+
+  it("Cancel Round rejects a round whose timer ran out", async () => {
+    const testRound = await createTestRound(seed + 7100, shortRoundConfig);
+    await buyKey(testRound);
+    await waitForRoundEnd(testRound.roundAccount);
+
+    try {
+      await cancelRound(testRound).rpc();
+      expect.fail("an ended round was cancelled");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("RoundOver");
+    }
+  });
+
+  // events of a confirmed transaction, by name
+  const eventsOf = async (signature: string) => {
+    const transaction = await connection.getTransaction(signature, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    const parser = new anchor.EventParser(program.programId, program.coder);
+    const events: Record<string, any> = {};
+    for (const event of parser.parseLogs(transaction.meta.logMessages)) {
+      events[event.name] = event.data;
+    }
+    return events;
+  };
+
+  it("Key purchases, cancels and refunds emit their events", async () => {
+    const testRound = await createTestRound(seed + 8000, shortRoundConfig);
+    const key = await buyKey(testRound);
+
+    const events = await eventsOf(key.signature);
+    const purchased = events.keyPurchased;
+    expect(purchased.round.toBase58()).to.equal(
+      testRound.roundAccount.toBase58()
+    );
+    expect(purchased.buyer.toBase58()).to.equal(provider.publicKey.toBase58());
+    expect(purchased.asset.toBase58()).to.equal(key.asset.toBase58());
+    expect(purchased.keyIndex.toNumber()).to.equal(1);
+    expect(purchased.quantity.toNumber()).to.equal(1);
+    expect(purchased.price.toNumber()).to.equal(10_000_000);
+    expect(purchased.teamFee.toNumber()).to.equal(420_000);
+    expect(purchased.referralFee.toNumber()).to.equal(0);
+    expect(purchased.poolFee.toNumber()).to.equal(690_000);
+    expect(purchased.burnFee.toNumber()).to.equal(990_000);
+    expect(purchased.treasureFee.toNumber()).to.equal(7_900_000);
+    expect(purchased.referrer).to.equal(null);
+    const round = await program.account.round.fetch(testRound.roundAccount);
+    expect(events.timerExtended.roundCloseSlot.toNumber()).to.equal(
+      round.roundCloseSlot.toNumber()
+    );
+
+    const cancelSignature = await cancelRound(testRound).rpc({
+      commitment: "confirmed",
+    });
+    const cancelled = (await eventsOf(cancelSignature)).roundCancelled;
+    expect(cancelled.round.toBase58()).to.equal(
+      testRound.roundAccount.toBase58()
+    );
+    // the burned 9.9% had to be topped up
+    expect(cancelled.shortfall.toNumber()).to.equal(990_000);
+
+    const refundSignature = await refundKey(testRound, key).rpc({
+      commitment: "confirmed",
+    });
+    const refunded = (await eventsOf(refundSignature)).keyRefunded;
+    expect(refunded.owner.toBase58()).to.equal(provider.publicKey.toBase58());
+    expect(refunded.keyIndex.toNumber()).to.equal(1);
+    expect(refunded.amount.toNumber()).to.equal(10_000_000);
+  });
+  it("Transfer fee mints fund vaults net of fees and cancel grosses up", async () => {
+    // 1% transfer fee on a 6 decimal Token-2022 mint
+    const feeMint = await createTransferFeeMint(
+      provider,
+      6,
+      100,
+      new BN(1_000_000_000_000)
+    );
+    const programConfigData = await program.account.programConfig.fetch(
+      programConfig
+    );
+    await program.methods
+      .updateConfig({
+        allowedMints: [tokenMint, NATIVE_MINT, feeMint],
+        feeRecipient: programConfigData.feeRecipient,
+        paused: false,
+      })
+      .accountsPartial({ authority: provider.publicKey, programConfig })
+      .rpc();
+    await mintToProvider(
+      provider,
+      feeMint,
+      TOKEN_2022_PROGRAM_ID,
+      new BN(1_000_000_000)
+    );
+
+    const testRound = await createTestRound(
+      seed + 9000,
+      shortRoundConfig,
+      feeMint,
+      TOKEN_2022_PROGRAM_ID
+    );
+
+    // the round only takes the mint it was created with
+    let round = await program.account.round.fetch(testRound.roundAccount);
+    expect(round.tokenMint.toBase58()).to.equal(feeMint.toBase58());
+    try {
+      await buyKey({
+        ...testRound,
+        tokenMint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        authorityAta: getAta(tokenMint, provider.publicKey, TOKEN_PROGRAM_ID),
+      });
+      expect.fail("a key was bought with another mint");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("InvalidMint");
+    }
+
+    const key = await buyKey(testRound);
+
+    // every vault receives its share less the 1% fee
+    expect(await tokenBalance(connection, testRound.poolVault)).to.equal(
+      690_000 - 6_900
+    );
+    expect(await tokenBalance(connection, testRound.teamVault)).to.equal(
+      420_000 - 4_200
+    );
+    expect(await tokenBalance(connection, testRound.treasureVault)).to.equal(
+      7_900_000 - 79_000
+    );
+    round = await program.account.round.fetch(testRound.roundAccount);
+    expect(round.poolUnallocated.toNumber()).to.equal(690_000 - 6_900);
+
+    await cancelRound(testRound).rpc();
+
+    // the top up covers its own transfer fee and the one of the refund
+    expect(await tokenBalance(connection, testRound.treasureVault)).to.equal(
+      10_000_000 + 101_011
+    );
+
+    const balanceBefore = await tokenBalance(
+      connection,
+      testRound.authorityAta
+    );
+    await refundKey(testRound, key).rpc();
+
+    // the holder gets back the full price
+    expect(
+      (await tokenBalance(connection, testRound.authorityAta)) - balanceBefore
+    ).to.equal(10_000_000);
+    expect(await tokenBalance(connection, testRound.treasureVault)).to.equal(0);
+    round = await program.account.round.fetch(testRound.roundAccount);
+    expect(round.refundableAmount.toNumber()).to.equal(0);
+    expect(round.refundFees.toNumber()).to.equal(0);
+  });
+  it("Thaw Leader unfreezes the winning key after the claim", async () => {
+    await program.methods
+      .thawLeader()
+      .accountsPartial({
+        authority: provider.publicKey,
+        roundAccount: prizeRound.roundAccount,
+        programConfig,
+        asset: prizeKeys[2].asset,
+        collection: prizeRound.collection,
+        logWrapper: null,
+        mplCore: MPL_CORE_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    const round = await program.account.round.fetch(prizeRound.roundAccount);
+    expect(round.leaderFrozen).to.equal(false);
+  });
+  it("Winner Claim rejects a wallet that does not hold the key", async () => {
+    // key #2 holds an unclaimed runner-up prize
+    try {
+      await winnerClaim(prizeRound, prizeKeys[1], referrer.publicKey)
+        .signers([referrer])
+        .rpc();
+      expect.fail("the prize was claimed by a wallet without the key");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("Unauthorized");
+    }
   });
 });
@@ -1,7 +1,153 @@
-import { web3 } from "@coral-xyz/anchor";
+import { AnchorProvider, web3 } from "@coral-xyz/anchor";
+import { BN } from "bn.js";
 export const getPDA = (
   seeds: Array<Buffer | Uint8Array>,
   programId: web3.PublicKey
 ) => {
   return web3.PublicKey.findProgramAddressSync(seeds, programId);
 };
+
+export const TOKEN_2022_PROGRAM_ID = new web3.PublicKey(
+  "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb"
+);
+const ASSOCIATED_TOKEN_PROGRAM_ID = new web3.PublicKey(
+  "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"
+);
+// base mint padded to an account, account type, TLV header, TransferFeeConfig
+const TRANSFER_FEE_MINT_SIZE = 165 + 1 + 4 + 108;
+
+export const sleep = (ms: number) =>
+  new Promise((resolve) => setTimeout(resolve, ms));
+
+// waits until the cluster is past `slot`
+export const waitForSlot = async (
+  connection: web3.Connection,
+  slot: number
+) => {
+  while ((await connection.getSlot()) <= slot) {
+    await sleep(400);
+  }
+};
+
+export const getAta = (
+  mint: web3.PublicKey,
+  owner: web3.PublicKey,
+  tokenProgram: web3.PublicKey
+) =>
+  web3.PublicKey.findProgramAddressSync(
+    [owner.toBuffer(), tokenProgram.toBuffer(), mint.toBuffer()],
+    ASSOCIATED_TOKEN_PROGRAM_ID
+  )[0];
+
+export const tokenBalance = async (
+  connection: web3.Connection,
+  tokenAccount: web3.PublicKey
+) =>
+  Number((await connection.getTokenAccountBalance(tokenAccount)).value.amount);
+
+// creates the token account of `owner`, paid by the provider
+export const createAta = async (
+  provider: AnchorProvider,
+  mint: web3.PublicKey,
+  owner: web3.PublicKey,
+  tokenProgram: web3.PublicKey
+) => {
+  const ata = getAta(mint, owner, tokenProgram);
+  const tx = new web3.Transaction().add(
+    new web3.TransactionInstruction({
+      programId: ASSOCIATED_TOKEN_PROGRAM_ID,
+      keys: [
+        { pubkey: provider.publicKey, isSigner: true, isWritable: true },
+        { pubkey: ata, isSigner: false, isWritable: true },
+        { pubkey: owner, isSigner: false, isWritable: false },
+        { pubkey: mint, isSigner: false, isWritable: false },
+        {
+          pubkey: web3.SystemProgram.programId,
+          isSigner: false,
+          isWritable: false,
+        },
+        { pubkey: tokenProgram, isSigner: false, isWritable: false },
+      ],
+      // CreateIdempotent
+      data: Buffer.from([1]),
+    })
+  );
+  await provider.sendAndConfirm(tx);
+  return ata;
+};
+
+// Token-2022 mint with a transfer fee, the provider is mint and fee authority
+export const createTransferFeeMint = async (
+  provider: AnchorProvider,
+  decimals: number,
+  feeBasisPoints: number,
+  maxFee: BN
+) => {
+  const mint = web3.Keypair.generate();
+  const authority = provider.publicKey.toBuffer();
+
+  const initializeTransferFeeConfig = Buffer.concat([
+    Buffer.from([26, 0, 1]),
+    authority,
+    Buffer.from([1]),
+    authority,
+    new BN(feeBasisPoints).toArrayLike(Buffer, "le", 2),
+    maxFee.toArrayLike(Buffer, "le", 8),
+  ]);
+  const initializeMint2 = Buffer.concat([
+    Buffer.from([20, decimals]),
+    authority,
+    Buffer.from([0]),
+  ]);
+
+  const tx = new web3.Transaction().add(
+    web3.SystemProgram.createAccount({
+      fromPubkey: provider.publicKey,
+      newAccountPubkey: mint.publicKey,
+      lamports: await provider.connection.getMinimumBalanceForRentExemption(
+        TRANSFER_FEE_MINT_SIZE
+      ),
+      space: TRANSFER_FEE_MINT_SIZE,
+      programId: TOKEN_2022_PROGRAM_ID,
+    }),
+    new web3.TransactionInstruction({
+      programId: TOKEN_2022_PROGRAM_ID,
+      keys: [{ pubkey: mint.publicKey, isSigner: false, isWritable: true }],
+      data: initializeTransferFeeConfig,
+    }),
+    new web3.TransactionInstruction({
+      programId: TOKEN_2022_PROGRAM_ID,
+      keys: [{ pubkey: mint.publicKey, isSigner: false, isWritable: true }],
+      data: initializeMint2,
+    })
+  );
+  await provider.sendAndConfirm(tx, [mint]);
+  return mint.publicKey;
+};
+
+// mints `amount` to the provider's token account, creating it if needed
+export const mintToProvider = async (
+  provider: AnchorProvider,
+  mint: web3.PublicKey,
+  tokenProgram: web3.PublicKey,
+  amount: BN
+) => {
+  const ata = await createAta(provider, mint, provider.publicKey, tokenProgram);
+  const tx = new web3.Transaction().add(
+    new web3.TransactionInstruction({
+      programId: tokenProgram,
+      keys: [
+        { pubkey: mint, isSigner: false, isWritable: true },
+        { pubkey: ata, isSigner: false, isWritable: true },
+        { pubkey: provider.publicKey, isSigner: true, isWritable: false },
+      ],
+      // MintTo
+      data: Buffer.concat([
+        Buffer.from([7]),
+        amount.toArrayLike(Buffer, "le", 8),
+      ]),
+    })
+  );
+  await provider.sendAndConfirm(tx);
+  return ata;
+};